@@ -0,0 +1,106 @@
+//! Generates the `NonTerminal` enum, the LL(1) parsing table and the FOLLOW
+//! sets used for error recovery from `grammer.g` into
+//! `$OUT_DIR/parsing_table.rs`.
+
+use std::{env, fmt::Write, fs, path::Path};
+
+#[path = "src/syntax/grammar.rs"]
+#[allow(dead_code)]
+mod grammar;
+
+use grammar::{Grammar, END};
+
+const GRAMMAR_PATH: &str = "grammer.g";
+
+fn main() {
+    println!("cargo:rerun-if-changed={GRAMMAR_PATH}");
+    println!("cargo:rerun-if-changed=src/syntax/grammar.rs");
+
+    let text = fs::read_to_string(GRAMMAR_PATH)
+        .unwrap_or_else(|err| panic!("cannot read {GRAMMAR_PATH}: {err}"));
+    let grammar = Grammar::parse(&text).unwrap_or_else(|err| panic!("{GRAMMAR_PATH}: {err}"));
+    let analysis = grammar.analyze();
+    let table = grammar.predict_table(&analysis);
+
+    let conflicts: Vec<String> = grammar
+        .conflicts(&analysis)
+        .iter()
+        .map(|conflict| {
+            let mut message = format!(
+                "{} conflict: {} on {} can expand to",
+                conflict.kind, conflict.non_terminal, conflict.terminal
+            );
+            for &index in &conflict.productions {
+                let production = &grammar.productions[index];
+                let _ = write!(message, "\n    {production} (line {})", production.line);
+            }
+            message
+        })
+        .collect();
+    if !conflicts.is_empty() {
+        panic!("{GRAMMAR_PATH} is not LL(1):\n  {}", conflicts.join("\n  "));
+    }
+
+    let mut out = String::new();
+    out.push_str("// Generated by build.rs from grammer.g, do not edit.\n\n");
+    out.push_str("/// The non-terminals of `grammer.g`, in order of their first production.\n");
+    out.push_str("#[derive(Debug, Clone, PartialEq, Eq, Hash, EnumString, Default, AsRefStr)]\n");
+    out.push_str("pub enum NonTerminal {\n    #[default]\n");
+    for non_terminal in &grammar.non_terminals {
+        let _ = writeln!(out, "    {non_terminal},");
+    }
+    out.push_str("}\n\n");
+
+    out.push_str("/// The LL(1) parsing table of `grammer.g`.\n");
+    out.push_str("pub fn add_rules() -> ParsingTable {\n");
+    out.push_str("    let mut parsing_table = ParsingTable::new();\n");
+    for ((non_terminal, terminal), productions) in &table {
+        let production = &grammar.productions[productions[0]];
+        let symbols: Vec<String> = production
+            .rhs
+            .iter()
+            .map(|symbol| {
+                if grammar.is_non_terminal(symbol) {
+                    format!("Symbol::NonTerminal(NonTerminal::{symbol})")
+                } else {
+                    format!("Symbol::Token(TokenType::{symbol})")
+                }
+            })
+            .collect();
+        let _ = writeln!(
+            out,
+            "    parsing_table.insert((NonTerminal::{non_terminal}, TokenType::{}), vec![{}]);",
+            token_type(terminal),
+            symbols.join(", ")
+        );
+    }
+    out.push_str("    parsing_table\n}\n\n");
+
+    out.push_str("/// The FOLLOW set of `non_terminal` in `grammer.g`.\n");
+    out.push_str("pub fn follow_set(non_terminal: &NonTerminal) -> &'static [TokenType] {\n");
+    out.push_str("    match non_terminal {\n");
+    for non_terminal in &grammar.non_terminals {
+        let follow: Vec<String> = analysis.follow[non_terminal]
+            .iter()
+            .map(|terminal| format!("TokenType::{}", token_type(terminal)))
+            .collect();
+        let _ = writeln!(
+            out,
+            "        NonTerminal::{non_terminal} => &[{}],",
+            follow.join(", ")
+        );
+    }
+    out.push_str("    }\n}\n");
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    fs::write(Path::new(&out_dir).join("parsing_table.rs"), out)
+        .expect("cannot write the parsing table");
+}
+
+fn token_type(terminal: &str) -> &str {
+    if terminal == END {
+        "End"
+    } else {
+        terminal
+    }
+}
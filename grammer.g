@@ -1,3 +1,6 @@
+# The grammar of the language, one production per line. `''` is the empty
+# string and `$` the end of input. build.rs turns this file into the
+# `NonTerminal` enum and the LL(1) parsing table, so it must stay LL(1).
 Program -> Declarations
 Declarations -> Declaration Declarations
 Declarations -> ''
@@ -13,11 +16,8 @@ Type -> T_Char
 MoreIdentifiers -> T_Comma Identifier Initialization MoreIdentifiers
 MoreIdentifiers -> ''
 Initialization -> T_Assign Expression
-Initialization -> T_LB IntegerLiteral T_RB Initialization
+Initialization -> T_LB Expression T_RB Initialization
 Initialization -> ''
-Functions -> Function Functions 
-Functions -> ''
-Function -> Type Identifier FunctionRest
 Parameters -> ParameterList
 Parameters -> ''
 ParameterList -> Parameter MoreParameters
@@ -28,33 +28,31 @@ Block -> T_LC Statements T_RC
 Statements -> Statement Statements
 Statements -> ''
 Statement -> Declaration
-Statement -> T_Id iddd T_Semicolon
-iddd -> FuncCall
-iddd -> Assignment 
+Statement -> Identifier Iddd T_Semicolon
 Statement -> IfStatement
 Statement -> ForStatement
 Statement -> PrintStatement T_Semicolon
 Statement -> ReturnStatement T_Semicolon
 Statement -> BreakStatement T_Semicolon
 Statement -> ContinueStatement T_Semicolon
+Iddd -> FuncCall
+Iddd -> Indices Assignment
 Assignment -> T_Assign Expression
-IfStatement -> T_If T_LP Expression T_RP Block ElseIfs ElseBlock
-ElseIfs -> ElseIf ElseIfs
-ElseIfs -> ''
-ElseIf -> T_Else T_If T_LP Expression T_RP Block
-ElseBlock -> T_Else Block
+IfStatement -> T_If T_LP Expression T_RP Block ElseBlock
+ElseBlock -> T_Else ElseBody
 ElseBlock -> ''
+ElseBody -> IfStatement
+ElseBody -> Block
 ForStatement -> T_For T_LP ForInit ForCondition T_Semicolon ForUpdate T_RP Block
-ForInit -> Assignment
 ForInit -> Declaration
-ForInit -> ''
+ForInit -> Identifier Iddd T_Semicolon
+ForInit -> T_Semicolon
 ForCondition -> Expression
 ForCondition -> ''
-ForUpdate -> Assignment
+ForUpdate -> Identifier Iddd
 ForUpdate -> ''
 PrintStatement -> T_Print T_LP PrintArguments T_RP
-PrintArguments -> StringLiteral MorePrintArguments
-PrintArguments -> Expression
+PrintArguments -> Expression MorePrintArguments
 MorePrintArguments -> T_Comma Expression MorePrintArguments
 MorePrintArguments -> ''
 ReturnStatement -> T_Return Expression
@@ -62,38 +60,42 @@ BreakStatement -> T_Break
 ContinueStatement -> T_Continue
 Expression -> LogicalOr
 LogicalOr -> LogicalAnd LogicalOrPRE
-LogicalOrPRE  -> T_LOp_OR LogicalAnd LogicalOrPRE
-LogicalOrPRE  -> ''
+LogicalOrPRE -> T_LOp_OR LogicalAnd LogicalOrPRE
+LogicalOrPRE -> ''
 LogicalAnd -> Equality LogicalAndPRE
-LogicalAndPRE  -> T_LOp_AND Equality LogicalAndPRE
-LogicalAndPRE  -> ''
+LogicalAndPRE -> T_LOp_AND Equality LogicalAndPRE
+LogicalAndPRE -> ''
 Equality -> Relational EqualityPRE
-EqualityPRE ->  T_ROp_E Relational EqualityPRE
-EqualityPRE  -> T_ROp_NE Relational EqualityPRE
-EqualityPRE  -> ''
+EqualityPRE -> T_ROp_E Relational EqualityPRE
+EqualityPRE -> T_ROp_NE Relational EqualityPRE
+EqualityPRE -> ''
 Relational -> Additive RelationalPRE
-RelationalPRE ->  T_ROp_L Additive RelationalPRE
-RelationalPRE ->  T_ROp_LE Additive RelationalPRE
-RelationalPRE ->  T_ROp_G Additive RelationalPRE
-RelationalPRE ->  T_ROp_GE Additive RelationalPRE
-RelationalPRE ->  ''
+RelationalPRE -> T_ROp_L Additive RelationalPRE
+RelationalPRE -> T_ROp_LE Additive RelationalPRE
+RelationalPRE -> T_ROp_G Additive RelationalPRE
+RelationalPRE -> T_ROp_GE Additive RelationalPRE
+RelationalPRE -> ''
 Additive -> Multiplicative AdditivePRE
-AdditivePRE  -> T_AOp_PL Multiplicative AdditivePRE
-AdditivePRE  -> T_AOp_MN Multiplicative AdditivePRE
-AdditivePRE  -> ''
+AdditivePRE -> T_AOp_PL Multiplicative AdditivePRE
+AdditivePRE -> T_AOp_MN Multiplicative AdditivePRE
+AdditivePRE -> ''
 Multiplicative -> Unary MultiplicativePRE
-MultiplicativePRE ->  T_AOp_ML Unary MultiplicativePRE
-MultiplicativePRE ->  T_AOp_DV Unary MultiplicativePRE
-MultiplicativePRE ->  T_AOp_RM Unary MultiplicativePRE
-MultiplicativePRE ->  ''
+MultiplicativePRE -> T_AOp_ML Unary MultiplicativePRE
+MultiplicativePRE -> T_AOp_DV Unary MultiplicativePRE
+MultiplicativePRE -> T_AOp_RM Unary MultiplicativePRE
+MultiplicativePRE -> ''
 Unary -> T_LOp_NOT Unary
 Unary -> Primary
-Primary -> Identifier
+Primary -> Identifier PrimaryRest
 Primary -> IntegerLiteral
 Primary -> BooleanLiteral
 Primary -> CharacterLiteral
 Primary -> StringLiteral
 Primary -> T_LP Expression T_RP
+PrimaryRest -> FuncCall
+PrimaryRest -> Indices
+Indices -> T_LB Expression T_RB Indices
+Indices -> ''
 Identifier -> T_Id
 IntegerLiteral -> T_Decimal
 IntegerLiteral -> T_Hexadecimal
@@ -101,11 +103,10 @@ BooleanLiteral -> T_True
 BooleanLiteral -> T_False
 CharacterLiteral -> T_Character
 StringLiteral -> T_String
-FuncCall -> T_LP ParametersCall T_RP T_Semicolon
+FuncCall -> T_LP ParametersCall T_RP
 ParametersCall -> ParameterListCa
 ParametersCall -> ''
 ParameterListCa -> ParameterCa MoreParametersCal
-ParameterCa -> Identifier
+ParameterCa -> Expression
 MoreParametersCal -> T_Comma ParameterCa MoreParametersCal
 MoreParametersCal -> ''
-Expression -> iddd 
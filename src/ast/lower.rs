@@ -0,0 +1,681 @@
+use slab_tree::{NodeRef, Tree};
+
+use crate::{
+    diagnostic::{code, Diagnostic, Diagnostics},
+    syntax::{NonTerminal, SymbolTree},
+    token::{Span, Token, TokenType},
+};
+
+use super::{
+    BinaryOp, Block, Else, Expr, FunctionDecl, Ident, Item, Literal, Param, Program, Stmt,
+    TypeName, UnaryOp, VarDecl,
+};
+
+/// The binary operator levels from the loosest to the tightest binding:
+/// `Level -> Operand LevelPRE` and `LevelPRE -> op Operand LevelPRE | ''`.
+const LEVELS: [(NonTerminal, NonTerminal); 6] = [
+    (NonTerminal::LogicalAnd, NonTerminal::LogicalOrPRE),
+    (NonTerminal::Equality, NonTerminal::LogicalAndPRE),
+    (NonTerminal::Relational, NonTerminal::EqualityPRE),
+    (NonTerminal::Additive, NonTerminal::RelationalPRE),
+    (NonTerminal::Multiplicative, NonTerminal::AdditivePRE),
+    (NonTerminal::Unary, NonTerminal::MultiplicativePRE),
+];
+
+/// Lowers a parse tree rooted at `Program` into a [`Program`].
+///
+/// Syntax errors have been reported by the parser, so the parts of the tree
+/// they broke are skipped silently. Integer literals that do not fit in an
+/// `int` and functions declared inside functions are reported into
+/// `diagnostics`.
+pub fn lower(tree: &Tree<SymbolTree>, diagnostics: &mut Diagnostics) -> Program {
+    let mut lower = Lower { diagnostics };
+    let mut items = vec![];
+    let mut declarations = tree
+        .root()
+        .and_then(|root| child(&root, NonTerminal::Declarations));
+    while let Some(node) = declarations {
+        if let Some(declaration) = child(&node, NonTerminal::Declaration) {
+            items.extend(lower.declaration(&declaration));
+        }
+        declarations = child(&node, NonTerminal::Declarations);
+    }
+    Program { items }
+}
+
+struct Lower<'a> {
+    diagnostics: &'a mut Diagnostics,
+}
+
+impl Lower<'_> {
+    /// `Declaration -> Type VarOrFunc`, one item per declared name.
+    fn declaration(&mut self, node: &NodeRef<SymbolTree>) -> Vec<Item> {
+        let Some(ty) = child(node, NonTerminal::Type).and_then(|ty| type_name(&ty)) else {
+            return vec![];
+        };
+        let Some(var_or_func) = child(node, NonTerminal::VarOrFunc) else {
+            return vec![];
+        };
+        let (Some(name), Some(rest)) = (
+            child(&var_or_func, NonTerminal::Identifier).and_then(|id| ident(&id)),
+            child(&var_or_func, NonTerminal::VarOrFuncRest),
+        ) else {
+            return vec![];
+        };
+
+        if let Some(function) = child(&rest, NonTerminal::FunctionRest) {
+            let mut params = vec![];
+            let mut list = child(&function, NonTerminal::Parameters)
+                .and_then(|params| child(&params, NonTerminal::ParameterList));
+            let mut param = list
+                .as_ref()
+                .and_then(|list| child(list, NonTerminal::Parameter));
+            while let Some(node) = param {
+                if let (Some(ty), Some(name)) = (
+                    child(&node, NonTerminal::Type).and_then(|ty| type_name(&ty)),
+                    child(&node, NonTerminal::Identifier).and_then(|id| ident(&id)),
+                ) {
+                    params.push(Param {
+                        name,
+                        ty,
+                        span: node.data().span(),
+                    });
+                }
+                list = list.and_then(|list| child(&list, NonTerminal::MoreParameters));
+                param = list
+                    .as_ref()
+                    .and_then(|list| child(list, NonTerminal::Parameter));
+            }
+            let body = self.block(child(&function, NonTerminal::Block), function.data().span());
+            return vec![Item::Function(FunctionDecl {
+                name,
+                return_type: ty,
+                params,
+                body,
+                span: node.data().span(),
+            })];
+        }
+
+        let Some(rest) = child(&rest, NonTerminal::VarDeclRest) else {
+            return vec![];
+        };
+        let mut items = vec![Item::Var(self.var_decl(
+            name,
+            ty,
+            child(&rest, NonTerminal::Initialization),
+        ))];
+        let mut more = child(&rest, NonTerminal::MoreIdentifiers);
+        while let Some(node) = more {
+            if let Some(name) = child(&node, NonTerminal::Identifier).and_then(|id| ident(&id)) {
+                items.push(Item::Var(self.var_decl(
+                    name,
+                    ty,
+                    child(&node, NonTerminal::Initialization),
+                )));
+            }
+            more = child(&node, NonTerminal::MoreIdentifiers);
+        }
+        items
+    }
+
+    /// `Initialization -> T_Assign Expression | T_LB Expression T_RB
+    /// Initialization | ''`
+    fn var_decl(
+        &mut self,
+        name: Ident,
+        ty: TypeName,
+        mut initialization: Option<NodeRef<SymbolTree>>,
+    ) -> VarDecl {
+        let mut span = name.span;
+        let mut dims = vec![];
+        let mut init = None;
+        while let Some(node) = initialization {
+            if token(&node, TokenType::T_Assign).is_some() {
+                let value = self.expression(child(&node, NonTerminal::Expression), &node);
+                span = span.to(value.span());
+                init = Some(value);
+                break;
+            }
+            if token(&node, TokenType::T_LB).is_some() {
+                let size = self.expression(child(&node, NonTerminal::Expression), &node);
+                span = span.to(token(&node, TokenType::T_RB).map_or(size.span(), |rb| rb.span));
+                dims.push(size);
+            }
+            initialization = child(&node, NonTerminal::Initialization);
+        }
+        VarDecl {
+            name,
+            ty,
+            dims,
+            init,
+            span,
+        }
+    }
+
+    /// `Block -> T_LC Statements T_RC`, an empty block at `span` when it is
+    /// missing.
+    fn block(&mut self, node: Option<NodeRef<SymbolTree>>, span: Span) -> Block {
+        let Some(node) = node else {
+            return Block {
+                stmts: vec![],
+                span,
+                close: span,
+            };
+        };
+        let mut stmts = vec![];
+        let mut statements = child(&node, NonTerminal::Statements);
+        while let Some(list) = statements {
+            if let Some(statement) = child(&list, NonTerminal::Statement) {
+                stmts.extend(self.statement(&statement));
+            }
+            statements = child(&list, NonTerminal::Statements);
+        }
+        let span = node.data().span();
+        Block {
+            stmts,
+            span,
+            close: token(&node, TokenType::T_RC).map_or(span, |token| token.span),
+        }
+    }
+
+    fn statement(&mut self, node: &NodeRef<SymbolTree>) -> Vec<Stmt> {
+        let span = node.data().span();
+        if let Some(declaration) = child(node, NonTerminal::Declaration) {
+            return self.local_declaration(&declaration);
+        }
+        if let Some(name) = child(node, NonTerminal::Identifier) {
+            return self
+                .assign_or_call(&name, child(node, NonTerminal::Iddd), span)
+                .into_iter()
+                .collect();
+        }
+        if let Some(statement) = child(node, NonTerminal::IfStatement) {
+            return vec![self.if_statement(&statement)];
+        }
+        if let Some(statement) = child(node, NonTerminal::ForStatement) {
+            return vec![self.for_statement(&statement)];
+        }
+        if let Some(statement) = child(node, NonTerminal::PrintStatement) {
+            let mut args = vec![];
+            let mut list = child(&statement, NonTerminal::PrintArguments);
+            while let Some(node) = list {
+                if let Some(expression) = child(&node, NonTerminal::Expression) {
+                    args.push(self.expression(Some(expression), &node));
+                }
+                list = child(&node, NonTerminal::MorePrintArguments);
+            }
+            return vec![Stmt::Print { args, span }];
+        }
+        if let Some(statement) = child(node, NonTerminal::ReturnStatement) {
+            let value = self.expression(child(&statement, NonTerminal::Expression), &statement);
+            return vec![Stmt::Return { value, span }];
+        }
+        if child(node, NonTerminal::BreakStatement).is_some() {
+            return vec![Stmt::Break(span)];
+        }
+        if child(node, NonTerminal::ContinueStatement).is_some() {
+            return vec![Stmt::Continue(span)];
+        }
+        vec![]
+    }
+
+    /// A declaration inside a function, where only variables may be
+    /// declared.
+    fn local_declaration(&mut self, node: &NodeRef<SymbolTree>) -> Vec<Stmt> {
+        self.declaration(node)
+            .into_iter()
+            .filter_map(|item| match item {
+                Item::Var(var) => Some(Stmt::Var(var)),
+                Item::Function(function) => {
+                    self.diagnostics.push(
+                        Diagnostic::error(
+                            code::NESTED_FUNCTION,
+                            format!(
+                                "function `{}` is declared inside a function",
+                                function.name.name
+                            ),
+                        )
+                        .with_span(function.name.span)
+                        .with_note("functions can only be declared at the top level"),
+                    );
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// `Identifier Iddd` with `Iddd -> FuncCall | Indices Assignment`.
+    fn assign_or_call(
+        &mut self,
+        name: &NodeRef<SymbolTree>,
+        iddd: Option<NodeRef<SymbolTree>>,
+        span: Span,
+    ) -> Option<Stmt> {
+        let name = ident(name)?;
+        let iddd = iddd?;
+        if let Some(call) = child(&iddd, NonTerminal::FuncCall) {
+            let expr = self.call(name, &call);
+            return Some(Stmt::ExprStmt { expr, span });
+        }
+        let target = self.indices(Expr::Var(name), child(&iddd, NonTerminal::Indices));
+        let assignment = child(&iddd, NonTerminal::Assignment)?;
+        let value = self.expression(child(&assignment, NonTerminal::Expression), &assignment);
+        Some(Stmt::Assign {
+            target,
+            value,
+            span,
+        })
+    }
+
+    /// `IfStatement -> T_If T_LP Expression T_RP Block ElseBlock`
+    fn if_statement(&mut self, node: &NodeRef<SymbolTree>) -> Stmt {
+        let span = node.data().span();
+        let cond = self.expression(child(node, NonTerminal::Expression), node);
+        let then = self.block(child(node, NonTerminal::Block), span);
+        let otherwise = child(node, NonTerminal::ElseBlock)
+            .and_then(|block| child(&block, NonTerminal::ElseBody))
+            .and_then(|body| {
+                if let Some(statement) = child(&body, NonTerminal::IfStatement) {
+                    Some(Else::If(Box::new(self.if_statement(&statement))))
+                } else {
+                    let block = child(&body, NonTerminal::Block)?;
+                    Some(Else::Block(self.block(Some(block), span)))
+                }
+            });
+        Stmt::If {
+            cond,
+            then,
+            otherwise,
+            span,
+        }
+    }
+
+    /// `ForStatement -> T_For T_LP ForInit ForCondition T_Semicolon ForUpdate
+    /// T_RP Block`
+    fn for_statement(&mut self, node: &NodeRef<SymbolTree>) -> Stmt {
+        let span = node.data().span();
+        let init = match child(node, NonTerminal::ForInit) {
+            Some(init) => {
+                if let Some(declaration) = child(&init, NonTerminal::Declaration) {
+                    self.local_declaration(&declaration)
+                } else if let Some(name) = child(&init, NonTerminal::Identifier) {
+                    let iddd = child(&init, NonTerminal::Iddd);
+                    self.assign_or_call(&name, iddd, init.data().span())
+                        .into_iter()
+                        .collect()
+                } else {
+                    vec![]
+                }
+            }
+            None => vec![],
+        };
+        let cond = child(node, NonTerminal::ForCondition)
+            .and_then(|cond| child(&cond, NonTerminal::Expression))
+            .map(|expression| self.expression(Some(expression), node));
+        let update = child(node, NonTerminal::ForUpdate).and_then(|update| {
+            let name = child(&update, NonTerminal::Identifier)?;
+            let iddd = child(&update, NonTerminal::Iddd);
+            self.assign_or_call(&name, iddd, update.data().span())
+        });
+        let body = self.block(child(node, NonTerminal::Block), span);
+        Stmt::For {
+            init,
+            cond,
+            update: update.map(Box::new),
+            body,
+            span,
+        }
+    }
+
+    /// Lowers an `Expression`, or returns an [`Expr::Error`] covering
+    /// `parent` when it is missing.
+    fn expression(
+        &mut self,
+        node: Option<NodeRef<SymbolTree>>,
+        parent: &NodeRef<SymbolTree>,
+    ) -> Expr {
+        match node.and_then(|node| child(&node, NonTerminal::LogicalOr)) {
+            Some(node) => self.binary(&node, 0),
+            None => Expr::Error(parent.data().span()),
+        }
+    }
+
+    /// Folds `Operand (op Operand)*` at `LEVELS[level]` to the left.
+    fn binary(&mut self, node: &NodeRef<SymbolTree>, level: usize) -> Expr {
+        let (operand, rest) = &LEVELS[level];
+        let mut lhs = self.operand(child(node, operand.clone()), node, level);
+        let mut pre = child(node, rest.clone());
+        while let Some(node) = pre {
+            let Some(op_token) = node
+                .children()
+                .find_map(|child| child.data().token().cloned())
+            else {
+                // `LevelPRE -> ''`
+                break;
+            };
+            let rhs = self.operand(child(&node, operand.clone()), &node, level);
+            lhs = Expr::Binary {
+                op: BinaryOp::from_token(&op_token.token).expect("a binary operator"),
+                op_span: op_token.span,
+                span: lhs.span().to(rhs.span()),
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+            };
+            pre = child(&node, rest.clone());
+        }
+        lhs
+    }
+
+    fn operand(
+        &mut self,
+        node: Option<NodeRef<SymbolTree>>,
+        parent: &NodeRef<SymbolTree>,
+        level: usize,
+    ) -> Expr {
+        match node {
+            Some(node) if level + 1 < LEVELS.len() => self.binary(&node, level + 1),
+            Some(node) => self.unary(&node),
+            None => Expr::Error(parent.data().span()),
+        }
+    }
+
+    /// `Unary -> T_LOp_NOT Unary | Primary`
+    fn unary(&mut self, node: &NodeRef<SymbolTree>) -> Expr {
+        if let Some(not) = token(node, TokenType::T_LOp_NOT) {
+            let operand = match child(node, NonTerminal::Unary) {
+                Some(operand) => self.unary(&operand),
+                None => Expr::Error(node.data().span()),
+            };
+            return Expr::Unary {
+                op: UnaryOp::Not,
+                op_span: not.span,
+                span: not.span.to(operand.span()),
+                operand: Box::new(operand),
+            };
+        }
+        match child(node, NonTerminal::Primary) {
+            Some(primary) => self.primary(&primary),
+            None => Expr::Error(node.data().span()),
+        }
+    }
+
+    fn primary(&mut self, node: &NodeRef<SymbolTree>) -> Expr {
+        if let Some(name) = child(node, NonTerminal::Identifier).and_then(|id| ident(&id)) {
+            let rest = child(node, NonTerminal::PrimaryRest);
+            if let Some(call) = rest
+                .as_ref()
+                .and_then(|rest| child(rest, NonTerminal::FuncCall))
+            {
+                return self.call(name, &call);
+            }
+            let indices = rest.and_then(|rest| child(&rest, NonTerminal::Indices));
+            return self.indices(Expr::Var(name), indices);
+        }
+        if token(node, TokenType::T_LP).is_some() {
+            return self.expression(child(node, NonTerminal::Expression), node);
+        }
+        match node.first_child() {
+            Some(literal) if literal.data().non_terminal().is_some() => {
+                self.primary_literal(&literal)
+            }
+            _ => Expr::Error(node.data().span()),
+        }
+    }
+
+    /// `IntegerLiteral`, `BooleanLiteral`, `CharacterLiteral` or
+    /// `StringLiteral`.
+    fn primary_literal(&mut self, node: &NodeRef<SymbolTree>) -> Expr {
+        let Some(token) = node.first_child().and_then(|t| t.data().token().cloned()) else {
+            return Expr::Error(node.data().span());
+        };
+        let literal = match token.token {
+            TokenType::T_Decimal | TokenType::T_Hexadecimal => {
+                let value = match token.token {
+                    TokenType::T_Hexadecimal => i32::from_str_radix(&token.literal[2..], 16),
+                    _ => token.literal.parse(),
+                };
+                match value {
+                    Ok(value) => Literal::Int(value),
+                    Err(_) => {
+                        self.diagnostics.push(
+                            Diagnostic::error(
+                                code::LITERAL_OUT_OF_RANGE,
+                                format!("`{}` does not fit in an `int`", token.literal),
+                            )
+                            .at(&token)
+                            .with_note(format!("the largest `int` is {}", i32::MAX)),
+                        );
+                        return Expr::Error(token.span);
+                    }
+                }
+            }
+            TokenType::T_True => Literal::Bool(true),
+            TokenType::T_False => Literal::Bool(false),
+            TokenType::T_Character => Literal::Char(
+                unescape(&token.literal, '\'')
+                    .chars()
+                    .next()
+                    .unwrap_or('\0'),
+            ),
+            TokenType::T_String => Literal::String(unescape(&token.literal, '"')),
+            _ => return Expr::Error(token.span),
+        };
+        Expr::Literal(literal, token.span)
+    }
+
+    /// `FuncCall -> T_LP ParametersCall T_RP`
+    fn call(&mut self, callee: Ident, node: &NodeRef<SymbolTree>) -> Expr {
+        let mut args = vec![];
+        let mut list = child(node, NonTerminal::ParametersCall)
+            .and_then(|params| child(&params, NonTerminal::ParameterListCa));
+        // `ParameterListCa -> ParameterCa MoreParametersCal` and
+        // `MoreParametersCal -> T_Comma ParameterCa MoreParametersCal | ''`
+        while let Some(node) = list.filter(|list| list.first_child().is_some()) {
+            let arg = child(&node, NonTerminal::ParameterCa)
+                .and_then(|arg| child(&arg, NonTerminal::Expression));
+            args.push(self.expression(arg, &node));
+            list = child(&node, NonTerminal::MoreParametersCal);
+        }
+        Expr::Call {
+            span: callee.span.to(node.data().span()),
+            callee,
+            args,
+        }
+    }
+
+    /// `Indices -> T_LB Expression T_RB Indices | ''`, applied to `array`.
+    fn indices(&mut self, mut array: Expr, mut indices: Option<NodeRef<SymbolTree>>) -> Expr {
+        while let Some(node) = indices {
+            if token(&node, TokenType::T_LB).is_none() {
+                break;
+            }
+            let index = self.expression(child(&node, NonTerminal::Expression), &node);
+            let end = token(&node, TokenType::T_RB).map_or(index.span(), |rb| rb.span);
+            array = Expr::Index {
+                span: array.span().to(end),
+                array: Box::new(array),
+                index: Box::new(index),
+            };
+            indices = child(&node, NonTerminal::Indices);
+        }
+        array
+    }
+}
+
+/// The first child of `node` that is `non_terminal`. Error nodes are never
+/// returned, so a part the parser gave up on is `None`.
+fn child<'a>(
+    node: &NodeRef<'a, SymbolTree>,
+    non_terminal: NonTerminal,
+) -> Option<NodeRef<'a, SymbolTree>> {
+    node.children()
+        .find(|child| child.data().is(non_terminal.clone()))
+}
+
+/// The first child of `node` that is a `token_type` token.
+fn token<'a>(node: &NodeRef<'a, SymbolTree>, token_type: TokenType) -> Option<&'a Token> {
+    node.children().find_map(|child| {
+        child
+            .data()
+            .token()
+            .filter(|token| token.token == token_type)
+    })
+}
+
+/// The name under `Identifier -> T_Id`.
+fn ident(node: &NodeRef<SymbolTree>) -> Option<Ident> {
+    let token = token(node, TokenType::T_Id)?;
+    Some(Ident {
+        name: token.literal.clone(),
+        span: token.span,
+    })
+}
+
+/// The type under `Type -> T_Int | T_Bool | T_Char`.
+fn type_name(node: &NodeRef<SymbolTree>) -> Option<TypeName> {
+    node.children()
+        .find_map(|child| TypeName::from_token(&child.data().token()?.token))
+}
+
+/// The text of a character or string literal between its `quote`s, with
+/// escape sequences resolved.
+fn unescape(literal: &str, quote: char) -> String {
+    let inner = literal.strip_prefix(quote).unwrap_or(literal);
+    let inner = inner.strip_suffix(quote).unwrap_or(inner);
+    let mut text = String::new();
+    let mut chars = inner.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            text.push(ch);
+            continue;
+        }
+        text.push(match chars.next() {
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('0') => '\0',
+            Some(ch) => ch,
+            None => '\\',
+        });
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::parser::Parser;
+
+    fn lower_source(source: &str) -> (Program, Diagnostics) {
+        let mut diagnostics = Diagnostics::new();
+        let tree = Parser::new(source.to_owned())
+            .parse(&mut diagnostics)
+            .unwrap();
+        let program = lower(&tree, &mut diagnostics);
+        (program, diagnostics)
+    }
+
+    #[test]
+    fn folds_operators_to_the_left_by_precedence() {
+        let (program, diagnostics) =
+            lower_source("int x = 10 - 3 - 2 * 4 / 2 % 3 < 5 == !true || a[1][i + 1] && f(a, b);");
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        assert_eq!(
+            program.to_string(),
+            "int x = (((((10 - 3) - (((2 * 4) / 2) % 3)) < 5) == !true) || (a[1][(i + 1)] && f(a, b)));\n"
+        );
+
+        let Item::Var(VarDecl {
+            init: Some(Expr::Binary { op, op_span, .. }),
+            ..
+        }) = &program.items[0]
+        else {
+            panic!("{:?}", program.items[0]);
+        };
+        assert_eq!((*op, op_span.start), (BinaryOp::Or, 44));
+    }
+
+    #[test]
+    fn lowers_declarations_and_statements() {
+        let source = "int g, h[2][3];\n\
+                      bool f(int a, char c) {\n\
+                      \x20 char s = '\\'';\n\
+                      \x20 for (int i = 0, j = 0x1F; i < j; i = i + 1) { if (a) { break; } else if (c) { continue; } else { h[i][0] = 1; } }\n\
+                      \x20 for (; ; f(a, c)) { }\n\
+                      \x20 print(\"a\\\"b\\n\", s);\n\
+                      \x20 return a == 1;\n\
+                      }\n";
+        let (program, diagnostics) = lower_source(source);
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        assert_eq!(
+            program.to_string(),
+            "int g;\n\
+             int h[2][3];\n\
+             bool f(int a, char c) {\n    \
+                 char s = '\\'';\n    \
+                 for (int i = 0, j = 31; (i < j); i = (i + 1)) {\n        \
+                     if (a) {\n            \
+                         break;\n        \
+                     } else if (c) {\n            \
+                         continue;\n        \
+                     } else {\n            \
+                         h[i][0] = 1;\n        \
+                     }\n    \
+                 }\n    \
+                 for (;; f(a, c)) {\n    \
+                 }\n    \
+                 print(\"a\\\"b\\n\", s);\n    \
+                 return (a == 1);\n\
+             }\n"
+        );
+
+        let Item::Function(function) = &program.items[2] else {
+            panic!("{:?}", program.items[2]);
+        };
+        let text = |span: Span| &source[span.start..span.end];
+        assert_eq!(text(function.span), source[16..].trim_end());
+        assert!(text(function.body.span).starts_with("{\n  char s"));
+        let Stmt::Print { args, .. } = &function.body.stmts[3] else {
+            panic!("{:?}", function.body.stmts[3]);
+        };
+        assert_eq!(
+            args[0],
+            Expr::Literal(Literal::String("a\"b\n".to_owned()), args[0].span())
+        );
+    }
+
+    #[test]
+    fn lowers_call_arguments_as_expressions() {
+        let (program, diagnostics) = lower_source("int x = f(1 + 2, g(y), 'c', !true);");
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        assert_eq!(
+            program.to_string(),
+            "int x = f((1 + 2), g(y), 'c', !true);\n"
+        );
+    }
+
+    #[test]
+    fn reports_large_literals_and_nested_functions() {
+        let (program, diagnostics) =
+            lower_source("int main() { int x = 2147483648; int y = 0x7fffffff; int f() { } }");
+        assert_eq!(
+            diagnostics.codes(),
+            [code::LITERAL_OUT_OF_RANGE, code::NESTED_FUNCTION]
+        );
+        assert_eq!(
+            program.to_string(),
+            "int main() {\n    int x = <error>;\n    int y = 2147483647;\n}\n"
+        );
+    }
+
+    #[test]
+    fn keeps_what_survives_syntax_errors() {
+        let (program, diagnostics) =
+            lower_source("int main() { int x = ; y = 1 +; print(x); if (x { } return 0; }");
+        assert!(diagnostics.has_errors());
+        assert_eq!(
+            program.to_string(),
+            "int main() {\n    int x = <error>;\n    y = (1 + <error>);\n    print(x);\n    if (x) {\n    }\n    return 0;\n}\n"
+        );
+    }
+}
@@ -0,0 +1,457 @@
+//! The abstract syntax tree, lowered from the parse tree by [`lower`].
+//!
+//! The helper non-terminals the LL(1) grammar needs (`LogicalOrPRE`,
+//! `VarOrFuncRest`, `Iddd`, ...) are gone: binary operators are folded into
+//! left associative [`Expr::Binary`] nodes and every declared name gets its
+//! own [`VarDecl`]. Parts lost to syntax errors are [`Expr::Error`]
+//! expressions, or are left out when a whole statement or declaration is
+//! missing.
+
+use std::fmt::{self, Write};
+
+use crate::token::{Span, TokenType};
+
+mod lower;
+
+pub use lower::lower;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Program {
+    pub items: Vec<Item>,
+}
+
+/// A top level declaration.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item {
+    Function(FunctionDecl),
+    Var(VarDecl),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionDecl {
+    pub name: Ident,
+    pub return_type: TypeName,
+    pub params: Vec<Param>,
+    pub body: Block,
+    /// From the return type to the closing brace.
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub name: Ident,
+    pub ty: TypeName,
+    pub span: Span,
+}
+
+/// One declared variable, `int a, b[2];` declares two.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VarDecl {
+    pub name: Ident,
+    pub ty: TypeName,
+    /// The sizes of the array dimensions, empty for scalars.
+    pub dims: Vec<Expr>,
+    pub init: Option<Expr>,
+    /// From the name to the end of the initializer.
+    pub span: Span,
+}
+
+/// A type as written in the source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TypeName {
+    Int,
+    Bool,
+    Char,
+}
+
+impl TypeName {
+    /// The type named by a `T_Int`, `T_Bool` or `T_Char` token.
+    pub fn from_token(token: &TokenType) -> Option<Self> {
+        match token {
+            TokenType::T_Int => Some(TypeName::Int),
+            TokenType::T_Bool => Some(TypeName::Bool),
+            TokenType::T_Char => Some(TypeName::Char),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TypeName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TypeName::Int => "int",
+            TypeName::Bool => "bool",
+            TypeName::Char => "char",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Ident {
+    pub name: String,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    pub stmts: Vec<Stmt>,
+    /// From `{` to `}`.
+    pub span: Span,
+    /// The `}`, or the whole block when it is missing.
+    pub close: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stmt {
+    Var(VarDecl),
+    /// `target = value;`, where `target` is an [`Expr::Var`] or an
+    /// [`Expr::Index`].
+    Assign {
+        target: Expr,
+        value: Expr,
+        span: Span,
+    },
+    /// A function call used as a statement.
+    ExprStmt {
+        expr: Expr,
+        span: Span,
+    },
+    If {
+        cond: Expr,
+        then: Block,
+        otherwise: Option<Else>,
+        span: Span,
+    },
+    For {
+        /// The declarations or the assignment before the first `;`.
+        init: Vec<Stmt>,
+        cond: Option<Expr>,
+        update: Option<Box<Stmt>>,
+        body: Block,
+        span: Span,
+    },
+    Return {
+        value: Expr,
+        span: Span,
+    },
+    Print {
+        args: Vec<Expr>,
+        span: Span,
+    },
+    Break(Span),
+    Continue(Span),
+}
+
+impl Stmt {
+    pub fn span(&self) -> Span {
+        match self {
+            Stmt::Var(var) => var.span,
+            Stmt::Assign { span, .. }
+            | Stmt::ExprStmt { span, .. }
+            | Stmt::If { span, .. }
+            | Stmt::For { span, .. }
+            | Stmt::Return { span, .. }
+            | Stmt::Print { span, .. }
+            | Stmt::Break(span)
+            | Stmt::Continue(span) => *span,
+        }
+    }
+}
+
+/// What follows `else`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Else {
+    /// `else if`, always a [`Stmt::If`].
+    If(Box<Stmt>),
+    Block(Block),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Literal(Literal, Span),
+    Var(Ident),
+    /// `array[index]`, `a[i][j]` indexes `a[i]`.
+    Index {
+        array: Box<Expr>,
+        index: Box<Expr>,
+        span: Span,
+    },
+    Call {
+        callee: Ident,
+        args: Vec<Expr>,
+        span: Span,
+    },
+    Unary {
+        op: UnaryOp,
+        op_span: Span,
+        operand: Box<Expr>,
+        span: Span,
+    },
+    Binary {
+        op: BinaryOp,
+        op_span: Span,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+        span: Span,
+    },
+    /// An expression lost to a syntax error or an invalid literal.
+    Error(Span),
+}
+
+impl Expr {
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Var(ident) => ident.span,
+            Expr::Literal(_, span)
+            | Expr::Index { span, .. }
+            | Expr::Call { span, .. }
+            | Expr::Unary { span, .. }
+            | Expr::Binary { span, .. }
+            | Expr::Error(span) => *span,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Int(i32),
+    Bool(bool),
+    /// The character with its escape sequence resolved.
+    Char(char),
+    String(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnaryOp {
+    Not,
+}
+
+impl fmt::Display for UnaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnaryOp::Not => f.write_str("!"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl BinaryOp {
+    /// The operator written as `token`.
+    pub fn from_token(token: &TokenType) -> Option<Self> {
+        Some(match token {
+            TokenType::T_LOp_OR => BinaryOp::Or,
+            TokenType::T_LOp_AND => BinaryOp::And,
+            TokenType::T_ROp_E => BinaryOp::Eq,
+            TokenType::T_ROp_NE => BinaryOp::Ne,
+            TokenType::T_ROp_L => BinaryOp::Lt,
+            TokenType::T_ROp_LE => BinaryOp::Le,
+            TokenType::T_ROp_G => BinaryOp::Gt,
+            TokenType::T_ROp_GE => BinaryOp::Ge,
+            TokenType::T_AOp_PL => BinaryOp::Add,
+            TokenType::T_AOp_MN => BinaryOp::Sub,
+            TokenType::T_AOp_ML => BinaryOp::Mul,
+            TokenType::T_AOp_DV => BinaryOp::Div,
+            TokenType::T_AOp_RM => BinaryOp::Rem,
+            _ => return None,
+        })
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BinaryOp::Or => "||",
+            BinaryOp::And => "&&",
+            BinaryOp::Eq => "==",
+            BinaryOp::Ne => "!=",
+            BinaryOp::Lt => "<",
+            BinaryOp::Le => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::Ge => ">=",
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Rem => "%",
+        }
+    }
+}
+
+impl fmt::Display for BinaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Prints the program back as source, with every binary operation in
+/// parentheses so the grouping is visible.
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for item in &self.items {
+            match item {
+                Item::Function(function) => {
+                    let params: Vec<String> = function
+                        .params
+                        .iter()
+                        .map(|param| format!("{} {}", param.ty, param.name.name))
+                        .collect();
+                    write!(
+                        f,
+                        "{} {}({}) ",
+                        function.return_type,
+                        function.name.name,
+                        params.join(", ")
+                    )?;
+                    write_block(f, &function.body, 0)?;
+                    f.write_char('\n')?;
+                }
+                Item::Var(var) => writeln!(f, "{var};")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_block(f: &mut fmt::Formatter<'_>, block: &Block, depth: usize) -> fmt::Result {
+    f.write_str("{\n")?;
+    for stmt in &block.stmts {
+        write!(f, "{:indent$}", "", indent = (depth + 1) * 4)?;
+        write_stmt(f, stmt, depth + 1)?;
+        f.write_char('\n')?;
+    }
+    write!(f, "{:indent$}}}", "", indent = depth * 4)
+}
+
+fn write_stmt(f: &mut fmt::Formatter<'_>, stmt: &Stmt, depth: usize) -> fmt::Result {
+    match stmt {
+        Stmt::If {
+            cond,
+            then,
+            otherwise,
+            ..
+        } => {
+            write!(f, "if ({cond}) ")?;
+            write_block(f, then, depth)?;
+            match otherwise {
+                Some(Else::If(stmt)) => {
+                    f.write_str(" else ")?;
+                    write_stmt(f, stmt, depth)
+                }
+                Some(Else::Block(block)) => {
+                    f.write_str(" else ")?;
+                    write_block(f, block, depth)
+                }
+                None => Ok(()),
+            }
+        }
+        Stmt::For {
+            init,
+            cond,
+            update,
+            body,
+            ..
+        } => {
+            f.write_str("for (")?;
+            for (i, stmt) in init.iter().enumerate() {
+                match stmt {
+                    Stmt::Var(var) if i > 0 => write!(f, ", {}", var.declarator())?,
+                    _ => write_simple(f, stmt)?,
+                }
+            }
+            f.write_char(';')?;
+            if let Some(cond) = cond {
+                write!(f, " {cond}")?;
+            }
+            f.write_char(';')?;
+            if let Some(update) = update {
+                f.write_char(' ')?;
+                write_simple(f, update)?;
+            }
+            f.write_str(") ")?;
+            write_block(f, body, depth)
+        }
+        Stmt::Return { value, .. } => write!(f, "return {value};"),
+        Stmt::Print { args, .. } => {
+            let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+            write!(f, "print({});", args.join(", "))
+        }
+        Stmt::Break(_) => f.write_str("break;"),
+        Stmt::Continue(_) => f.write_str("continue;"),
+        Stmt::Var(_) | Stmt::Assign { .. } | Stmt::ExprStmt { .. } => {
+            write_simple(f, stmt)?;
+            f.write_char(';')
+        }
+    }
+}
+
+/// Writes a declaration, assignment or call without its `;`.
+fn write_simple(f: &mut fmt::Formatter<'_>, stmt: &Stmt) -> fmt::Result {
+    match stmt {
+        Stmt::Var(var) => write!(f, "{var}"),
+        Stmt::Assign { target, value, .. } => write!(f, "{target} = {value}"),
+        Stmt::ExprStmt { expr, .. } => write!(f, "{expr}"),
+        _ => write_stmt(f, stmt, 0),
+    }
+}
+
+impl VarDecl {
+    /// The declaration without its type: `a[2] = 1`.
+    fn declarator(&self) -> String {
+        let mut s = self.name.name.clone();
+        for dim in &self.dims {
+            let _ = write!(s, "[{dim}]");
+        }
+        if let Some(init) = &self.init {
+            let _ = write!(s, " = {init}");
+        }
+        s
+    }
+}
+
+impl fmt::Display for VarDecl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.ty, self.declarator())
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Literal(literal, _) => write!(f, "{literal}"),
+            Expr::Var(ident) => f.write_str(&ident.name),
+            Expr::Index { array, index, .. } => write!(f, "{array}[{index}]"),
+            Expr::Call { callee, args, .. } => {
+                let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+                write!(f, "{}({})", callee.name, args.join(", "))
+            }
+            Expr::Unary { op, operand, .. } => write!(f, "{op}{operand}"),
+            Expr::Binary { op, lhs, rhs, .. } => write!(f, "({lhs} {op} {rhs})"),
+            Expr::Error(_) => f.write_str("<error>"),
+        }
+    }
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Literal::Int(value) => write!(f, "{value}"),
+            Literal::Bool(value) => write!(f, "{value}"),
+            Literal::Char(value) => write!(f, "'{}'", value.escape_default()),
+            Literal::String(value) => write!(f, "\"{}\"", value.escape_default()),
+        }
+    }
+}
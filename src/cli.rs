@@ -0,0 +1,350 @@
+use std::{fmt, path::PathBuf, str::FromStr};
+
+use compiler::{
+    diagnostic::{Level, Lints},
+    ir::opt::OptLevel,
+};
+
+pub(crate) const USAGE: &str = "\
+Usage: compiler <COMMAND> [OPTIONS] [FILES]...
+
+Commands:
+  lex      Print the token stream of each file
+  parse    Print the parse tree of each file
+  ast      Print the AST of each file as source, with binary operations in
+           parentheses
+  check    Parse and run the semantic checks on each file
+  ir       Check each file and print its three-address IR
+  cfg      Check each file and print the control-flow graph of each
+           function as Graphviz DOT
+  run      Check each file and run it from `main`, exiting with what
+           `main` returns
+  grammar  Print the FIRST/FOLLOW sets and predict table of a grammar and
+           report its LL(1) conflicts [default FILES: the built-in grammer.g]
+
+Options:
+  -f, --format <FORMAT>  Output format: text, debug, json [default: text]
+      --color <WHEN>     Color diagnostics: auto, always, never [default: auto]
+  -A, --allow <LINT>     Do not report LINT
+  -W, --warn <LINT>      Report LINT as a warning [default for all lints]
+  -D, --deny <LINT>      Report LINT as an error
+  -O, --opt-level <N>    Optimize the IR of `ir` and `cfg`: 0 for none, 1 to
+                         fold constants, simplify the control flow and
+                         remove dead code, 2 to also go through SSA form
+                         [default: 1]
+      --dump-passes      With `ir`, also print the IR as lowered and after
+                         every optimization pass that changes it
+      --stats            With `ir` and `cfg`, report on stderr how many
+                         instructions every function has as lowered and
+                         after optimizing
+  -h, --help             Print this help
+
+Lints: unused-variables, unused-parameters, unused-assignments,
+unused-functions, or unused for all of them. Names starting with `_` are
+never reported as unused.
+
+With no FILES, or when FILES is -, the source is read from stdin.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Command {
+    Lex,
+    Parse,
+    Ast,
+    Check,
+    Ir,
+    Cfg,
+    Run,
+    Grammar,
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lex" => Ok(Command::Lex),
+            "parse" => Ok(Command::Parse),
+            "ast" => Ok(Command::Ast),
+            "check" => Ok(Command::Check),
+            "ir" => Ok(Command::Ir),
+            "cfg" => Ok(Command::Cfg),
+            "run" => Ok(Command::Run),
+            "grammar" => Ok(Command::Grammar),
+            _ => Err(format!("unknown command `{s}`")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum Format {
+    #[default]
+    Text,
+    Debug,
+    /// One JSON document per file, see the `json` module for the schema.
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "debug" => Ok(Format::Debug),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown format `{s}`")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum ColorChoice {
+    /// Color when stderr is a terminal and `NO_COLOR` is not set.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(format!("unknown color choice `{s}`")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Input {
+    Stdin,
+    Path(PathBuf),
+    /// The grammar the parser was built from.
+    Grammar,
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Input::Stdin => write!(f, "<stdin>"),
+            Input::Path(path) => write!(f, "{}", path.display()),
+            Input::Grammar => write!(f, "grammer.g"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Args {
+    Help,
+    Run(Options),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Options {
+    pub command: Command,
+    pub format: Format,
+    pub color: ColorChoice,
+    pub lints: Lints,
+    pub opt_level: OptLevel,
+    pub dump_passes: bool,
+    pub stats: bool,
+    pub inputs: Vec<Input>,
+}
+
+impl Args {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut args = args.into_iter();
+        let mut command = None;
+        let mut format = Format::default();
+        let mut color = ColorChoice::default();
+        let mut lints = Lints::default();
+        let mut opt_level = OptLevel::default();
+        let mut dump_passes = false;
+        let mut stats = false;
+        let mut inputs = vec![];
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(Args::Help),
+                "-f" | "--format" => {
+                    let value = args.next().ok_or(format!("`{arg}` needs a value"))?;
+                    format = value.parse()?;
+                }
+                "--color" => {
+                    let value = args.next().ok_or(format!("`{arg}` needs a value"))?;
+                    color = value.parse()?;
+                }
+                "-A" | "--allow" | "-W" | "--warn" | "-D" | "--deny" => {
+                    let value = args.next().ok_or(format!("`{arg}` needs a value"))?;
+                    let level = match arg.as_str() {
+                        "-A" | "--allow" => Level::Allow,
+                        "-W" | "--warn" => Level::Warn,
+                        _ => Level::Deny,
+                    };
+                    lints.set_by_name(&value, level)?;
+                }
+                "-O" | "--opt-level" => {
+                    let value = args.next().ok_or(format!("`{arg}` needs a value"))?;
+                    opt_level = value.parse()?;
+                }
+                "--dump-passes" => dump_passes = true,
+                "--stats" => stats = true,
+                "-" => inputs.push(Input::Stdin),
+                _ if arg.starts_with("--format=") => {
+                    format = arg["--format=".len()..].parse()?;
+                }
+                _ if arg.starts_with("--color=") => {
+                    color = arg["--color=".len()..].parse()?;
+                }
+                _ if arg.starts_with("--opt-level=") => {
+                    opt_level = arg["--opt-level=".len()..].parse()?;
+                }
+                _ if arg.starts_with("-O") => opt_level = arg["-O".len()..].parse()?,
+                _ if arg.starts_with('-') => return Err(format!("unknown option `{arg}`")),
+                _ if command.is_none() => command = Some(arg.parse()?),
+                _ => inputs.push(Input::Path(PathBuf::from(arg))),
+            }
+        }
+
+        let command = command.ok_or("no command given")?;
+        if inputs.is_empty() {
+            inputs.push(match command {
+                Command::Grammar => Input::Grammar,
+                _ => Input::Stdin,
+            });
+        }
+        Ok(Args::Run(Options {
+            command,
+            format,
+            color,
+            lints,
+            opt_level,
+            dump_passes,
+            stats,
+            inputs,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use compiler::diagnostic::Lint;
+
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_command_format_and_inputs() {
+        assert_eq!(
+            parse(&[
+                "check",
+                "--format",
+                "debug",
+                "a.c",
+                "-",
+                "--color=never",
+                "b.c"
+            ]),
+            Ok(Args::Run(Options {
+                command: Command::Check,
+                format: Format::Debug,
+                color: ColorChoice::Never,
+                lints: Lints::default(),
+                opt_level: OptLevel::O1,
+                dump_passes: false,
+                stats: false,
+                inputs: vec![
+                    Input::Path("a.c".into()),
+                    Input::Stdin,
+                    Input::Path("b.c".into())
+                ],
+            }))
+        );
+        assert_eq!(
+            parse(&["grammar"]),
+            Ok(Args::Run(Options {
+                command: Command::Grammar,
+                format: Format::Text,
+                color: ColorChoice::Auto,
+                lints: Lints::default(),
+                opt_level: OptLevel::O1,
+                dump_passes: false,
+                stats: false,
+                inputs: vec![Input::Grammar],
+            }))
+        );
+        assert_eq!(
+            parse(&["lex"]),
+            Ok(Args::Run(Options {
+                command: Command::Lex,
+                format: Format::Text,
+                color: ColorChoice::Auto,
+                lints: Lints::default(),
+                opt_level: OptLevel::O1,
+                dump_passes: false,
+                stats: false,
+                inputs: vec![Input::Stdin],
+            }))
+        );
+    }
+
+    #[test]
+    fn applies_lint_levels_in_order() {
+        let Ok(Args::Run(options)) =
+            parse(&["check", "-A", "unused", "--deny", "unused-functions"])
+        else {
+            panic!("the arguments are valid");
+        };
+        let mut lints = Lints::allow_all();
+        lints.set(Lint::UnusedFunctions, Level::Deny);
+        assert_eq!(options.lints, lints);
+    }
+
+    #[test]
+    fn parses_optimization_options() {
+        for args in [
+            &["ir", "-O0"][..],
+            &["ir", "-O", "0"],
+            &["ir", "--opt-level=0"],
+            &["ir", "-O1", "--opt-level", "0"],
+        ] {
+            let Ok(Args::Run(options)) = parse(args) else {
+                panic!("{args:?} are valid");
+            };
+            assert_eq!(options.opt_level, OptLevel::O0);
+            assert!(!options.dump_passes);
+            assert!(!options.stats);
+        }
+        let Ok(Args::Run(options)) = parse(&["ir", "--dump-passes"]) else {
+            panic!("the arguments are valid");
+        };
+        assert_eq!(options.opt_level, OptLevel::O1);
+        assert!(options.dump_passes);
+        let Ok(Args::Run(options)) = parse(&["cfg", "-O2", "--stats"]) else {
+            panic!("the arguments are valid");
+        };
+        assert_eq!(options.opt_level, OptLevel::O2);
+        assert!(options.stats);
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["build"]).is_err());
+        assert!(parse(&["lex", "--format"]).is_err());
+        assert!(parse(&["lex", "--format=yaml"]).is_err());
+        assert!(parse(&["lex", "--verbose"]).is_err());
+        assert!(parse(&["lex", "--color", "sometimes"]).is_err());
+        assert!(parse(&["check", "-D", "unused-imports"]).is_err());
+        assert!(parse(&["ir", "-O3"]).is_err());
+        assert!(parse(&["ir", "-O"]).is_err());
+        assert_eq!(parse(&["lex", "-h"]), Ok(Args::Help));
+    }
+}
@@ -0,0 +1,139 @@
+use std::{fmt, str::FromStr};
+
+use super::{code, Diagnostic, Severity};
+
+/// A warning that can be turned off or into an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lint {
+    /// A local variable that is never used.
+    UnusedVariables,
+    /// A parameter that is never used.
+    UnusedParameters,
+    /// A local variable or parameter that is assigned but never read.
+    UnusedAssignments,
+    /// A function that `main` never calls, directly or through other
+    /// functions.
+    UnusedFunctions,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 4] = [
+        Lint::UnusedVariables,
+        Lint::UnusedParameters,
+        Lint::UnusedAssignments,
+        Lint::UnusedFunctions,
+    ];
+
+    /// The name used on the command line, e.g. `unused-variables`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::UnusedVariables => "unused-variables",
+            Lint::UnusedParameters => "unused-parameters",
+            Lint::UnusedAssignments => "unused-assignments",
+            Lint::UnusedFunctions => "unused-functions",
+        }
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Lint::UnusedVariables | Lint::UnusedParameters => code::UNUSED_VARIABLE,
+            Lint::UnusedAssignments => code::UNUSED_ASSIGNMENT,
+            Lint::UnusedFunctions => code::UNUSED_FUNCTION,
+        }
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Level {
+    /// Not reported.
+    Allow,
+    /// Reported as a warning.
+    #[default]
+    Warn,
+    /// Reported as an error.
+    Deny,
+}
+
+/// The level of every [`Lint`], all [`Level::Warn`] by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Lints {
+    levels: [Level; Lint::ALL.len()],
+}
+
+impl Lints {
+    /// Every lint at [`Level::Allow`].
+    pub fn allow_all() -> Self {
+        Self {
+            levels: [Level::Allow; Lint::ALL.len()],
+        }
+    }
+
+    pub fn level(&self, lint: Lint) -> Level {
+        self.levels[lint as usize]
+    }
+
+    pub fn set(&mut self, lint: Lint, level: Level) {
+        self.levels[lint as usize] = level;
+    }
+
+    /// Sets the level of the lint called `name`, or of all of them for
+    /// `unused`.
+    pub fn set_by_name(&mut self, name: &str, level: Level) -> Result<(), String> {
+        if name == "unused" {
+            self.levels = [level; Lint::ALL.len()];
+            return Ok(());
+        }
+        self.set(name.parse()?, level);
+        Ok(())
+    }
+
+    /// A diagnostic for `lint` at its level, or `None` when it is allowed.
+    pub fn diagnostic(&self, lint: Lint, message: impl Into<String>) -> Option<Diagnostic> {
+        let diagnostic = match self.level(lint) {
+            Level::Allow => return None,
+            Level::Warn => Diagnostic::warning(lint.code(), message),
+            Level::Deny => Diagnostic::new(Severity::Error, lint.code(), message)
+                .with_note(format!("`{lint}` is denied")),
+        };
+        Some(diagnostic)
+    }
+}
+
+impl FromStr for Lint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Lint::ALL
+            .into_iter()
+            .find(|lint| lint.name() == s)
+            .ok_or_else(|| format!("unknown lint `{s}`"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sets_levels_by_name_and_group() {
+        let mut lints = Lints::default();
+        assert_eq!(lints.level(Lint::UnusedFunctions), Level::Warn);
+
+        lints.set_by_name("unused", Level::Allow).unwrap();
+        lints.set_by_name("unused-parameters", Level::Deny).unwrap();
+        assert!(lints.set_by_name("unused-imports", Level::Deny).is_err());
+
+        assert_eq!(lints.diagnostic(Lint::UnusedVariables, "x"), None);
+        let denied = lints.diagnostic(Lint::UnusedParameters, "p").unwrap();
+        assert_eq!(
+            (denied.severity, denied.code),
+            (Severity::Error, code::UNUSED_VARIABLE)
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use std::fmt;
+
+use crate::token::{Span, Token};
+
+mod lint;
+mod render;
+
+pub use lint::{Level, Lint, Lints};
+pub use render::Renderer;
+
+/// Stable error codes, so tools can match on a diagnostic without parsing
+/// its message.
+///
+/// `E00xx` are lexical errors, `E01xx` syntax errors, `E02xx` semantic
+/// errors, `E03xx` problems in a grammar file and `E04xx` errors of a
+/// running program.
+pub mod code {
+    pub const ILLEGAL_CHARACTER: &str = "E0001";
+
+    pub const UNEXPECTED_TOKEN: &str = "E0100";
+    pub const EXPECTED_TOKEN: &str = "E0101";
+    pub const UNEXPECTED_END: &str = "E0102";
+    pub const TRAILING_INPUT: &str = "E0103";
+
+    pub const DUPLICATE_DECLARATION: &str = "E0200";
+    pub const TYPE_MISMATCH: &str = "E0201";
+    pub const INVALID_ARRAY_SIZE: &str = "E0202";
+    pub const CALL_MISMATCH: &str = "E0203";
+    pub const RETURN_TYPE_MISMATCH: &str = "E0204";
+    pub const UNDECLARED: &str = "E0205";
+    pub const MISSING_MAIN: &str = "E0206";
+    pub const LITERAL_OUT_OF_RANGE: &str = "E0207";
+    pub const NESTED_FUNCTION: &str = "E0208";
+    pub const MISSING_RETURN: &str = "E0209";
+    pub const UNREACHABLE_CODE: &str = "E0210";
+    pub const LOOP_CONTROL_OUTSIDE_LOOP: &str = "E0211";
+    pub const UNINITIALIZED: &str = "E0212";
+    pub const UNUSED_VARIABLE: &str = "E0213";
+    pub const UNUSED_ASSIGNMENT: &str = "E0214";
+    pub const UNUSED_FUNCTION: &str = "E0215";
+    pub const CONSTANT_DIVISION_BY_ZERO: &str = "E0216";
+    pub const CONSTANT_OVERFLOW: &str = "E0217";
+
+    pub const GRAMMAR_SYNTAX: &str = "E0300";
+    pub const LL1_CONFLICT: &str = "E0301";
+    pub const LEFT_RECURSION: &str = "E0302";
+    pub const UNPRODUCTIVE: &str = "E0303";
+    pub const UNREACHABLE: &str = "E0304";
+    pub const UNKNOWN_TERMINAL: &str = "E0305";
+
+    pub const DIVISION_BY_ZERO: &str = "E0400";
+    pub const INDEX_OUT_OF_BOUNDS: &str = "E0401";
+    pub const OUTPUT_FAILED: &str = "E0402";
+    pub const INTEGER_OVERFLOW: &str = "E0403";
+    pub const STACK_OVERFLOW: &str = "E0404";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        })
+    }
+}
+
+/// A secondary location attached to a diagnostic, e.g. an earlier
+/// declaration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// One of the constants in [`code`].
+    pub code: &'static str,
+    pub message: String,
+    /// Where the problem is, `None` for problems with the whole file.
+    pub span: Option<Span>,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            code,
+            message: message.into(),
+            span: None,
+            labels: vec![],
+            notes: vec![],
+        }
+    }
+
+    pub fn error(code: &'static str, message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, code, message)
+    }
+
+    pub fn warning(code: &'static str, message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, code, message)
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// Points the diagnostic at `token`.
+    pub fn at(self, token: &Token) -> Self {
+        self.with_span(token.span)
+    }
+
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+        });
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    /// A single line summary: `error[E0201]: message (line 3, column 5)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.code, self.message)?;
+        if let Some(span) = &self.span {
+            write!(f, " (line {}, column {})", span.line, span.column)?;
+        }
+        Ok(())
+    }
+}
+
+/// The sink every phase pushes its diagnostics into.
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic> {
+        self.diagnostics.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.iter()
+            .filter(|d| d.severity == Severity::Error)
+            .count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.error_count() > 0
+    }
+
+    /// The codes of all diagnostics, in the order they were reported.
+    pub fn codes(&self) -> Vec<&'static str> {
+        self.iter().map(|d| d.code).collect()
+    }
+
+    pub fn into_vec(self) -> Vec<Diagnostic> {
+        self.diagnostics
+    }
+}
+
+impl<'a> IntoIterator for &'a Diagnostics {
+    type Item = &'a Diagnostic;
+    type IntoIter = std::slice::Iter<'a, Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Extend<Diagnostic> for Diagnostics {
+    fn extend<T: IntoIterator<Item = Diagnostic>>(&mut self, iter: T) {
+        self.diagnostics.extend(iter);
+    }
+}
@@ -0,0 +1,285 @@
+use std::fmt::Write;
+
+use crate::token::Span;
+
+use super::{Diagnostic, Severity};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const GREEN: &str = "\x1b[1;32m";
+const BLUE: &str = "\x1b[1;34m";
+
+const TAB_WIDTH: usize = 4;
+
+/// Renders diagnostics the way rustc does: a `file:line:col` header, the
+/// source lines involved, and carets under the spans.
+///
+/// ```text
+/// error[E0200]: `x` is already declared in this block
+///  --> main.c:3:9
+///   |
+/// 2 |     int x = 1;
+///   |         - first declared here
+/// 3 |     int x = 2;
+///   |         ^
+/// ```
+///
+/// Without a span the location is the file alone, ` --> main.c`.
+pub struct Renderer<'a> {
+    source: &'a str,
+    file_name: &'a str,
+    color: bool,
+}
+
+struct Mark<'a> {
+    span: Span,
+    primary: bool,
+    message: &'a str,
+}
+
+impl<'a> Renderer<'a> {
+    pub fn new(source: &'a str, file_name: &'a str) -> Self {
+        Self {
+            source,
+            file_name,
+            color: false,
+        }
+    }
+
+    /// Whether to use ANSI colors, off by default.
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let mut out = String::new();
+        let severity_style = match diagnostic.severity {
+            Severity::Error => RED,
+            Severity::Warning => YELLOW,
+            Severity::Note => GREEN,
+        };
+        let _ = writeln!(
+            out,
+            "{}{}[{}]{}{}: {}{}",
+            self.style(severity_style),
+            diagnostic.severity,
+            diagnostic.code,
+            self.style(RESET),
+            self.style(BOLD),
+            diagnostic.message,
+            self.style(RESET),
+        );
+
+        let mut marks: Vec<Mark> = diagnostic
+            .span
+            .iter()
+            .map(|&span| Mark {
+                span,
+                primary: true,
+                message: "",
+            })
+            .chain(diagnostic.labels.iter().map(|label| Mark {
+                span: label.span,
+                primary: false,
+                message: &label.message,
+            }))
+            .collect();
+        marks.sort_by_key(|mark| (mark.span.line, mark.span.column));
+
+        let width = marks
+            .iter()
+            .map(|mark| mark.span.line.to_string().len())
+            .max()
+            .unwrap_or(1);
+
+        let location = match diagnostic.span.or(marks.first().map(|mark| mark.span)) {
+            Some(span) => format!("{}:{}:{}", self.file_name, span.line, span.column),
+            None => self.file_name.to_owned(),
+        };
+        let _ = writeln!(
+            out,
+            "{:width$}{}-->{} {location}",
+            "",
+            self.style(BLUE),
+            self.style(RESET),
+        );
+
+        if !marks.is_empty() {
+            self.gutter(&mut out, width, "");
+            let mut previous_line = None;
+            for (line, group) in group_by_line(&marks) {
+                if previous_line.is_some_and(|previous| line > previous + 1) {
+                    let _ = writeln!(out, "{}...{}", self.style(BLUE), self.style(RESET));
+                }
+                previous_line = Some(line);
+                self.snippet(&mut out, width, line, &group, severity_style);
+            }
+        }
+
+        for note in &diagnostic.notes {
+            let _ = writeln!(
+                out,
+                "{:width$} {}={} {}note{}: {}",
+                "",
+                self.style(BLUE),
+                self.style(RESET),
+                self.style(BOLD),
+                self.style(RESET),
+                note
+            );
+        }
+        out
+    }
+
+    fn snippet(
+        &self,
+        out: &mut String,
+        width: usize,
+        line: usize,
+        marks: &[&Mark],
+        primary_style: &str,
+    ) {
+        let text = self
+            .source
+            .lines()
+            .nth(line.saturating_sub(1))
+            .unwrap_or("");
+        let _ = writeln!(
+            out,
+            "{}{:>width$} |{} {}",
+            self.style(BLUE),
+            line,
+            self.style(RESET),
+            expand_tabs(text)
+        );
+
+        for mark in marks {
+            // Multi-line spans are underlined up to the end of their first line.
+            let len = self
+                .source
+                .get(mark.span.start..mark.span.end)
+                .map_or(0, |text| text.chars().take_while(|&ch| ch != '\n').count());
+            let start = display_column(text, mark.span.column.saturating_sub(1));
+            let end = display_column(text, mark.span.column.saturating_sub(1) + len);
+            let (ch, style) = if mark.primary {
+                ('^', primary_style)
+            } else {
+                ('-', BLUE)
+            };
+            let underline = ch.to_string().repeat((end - start).max(1));
+            let mut annotation = format!(
+                "{:start$}{}{}",
+                "",
+                self.style(style),
+                underline,
+                start = start
+            );
+            if !mark.message.is_empty() {
+                let _ = write!(annotation, " {}", mark.message);
+            }
+            let _ = write!(annotation, "{}", self.style(RESET));
+            self.gutter(out, width, &annotation);
+        }
+    }
+
+    fn gutter(&self, out: &mut String, width: usize, rest: &str) {
+        let _ = writeln!(
+            out,
+            "{:width$} {}|{}{}{}",
+            "",
+            self.style(BLUE),
+            self.style(RESET),
+            if rest.is_empty() { "" } else { " " },
+            rest
+        );
+    }
+
+    fn style(&self, style: &'a str) -> &'a str {
+        if self.color {
+            style
+        } else {
+            ""
+        }
+    }
+}
+
+fn group_by_line<'m, 'a>(marks: &'m [Mark<'a>]) -> Vec<(usize, Vec<&'m Mark<'a>>)> {
+    let mut groups: Vec<(usize, Vec<&Mark>)> = vec![];
+    for mark in marks {
+        match groups.last_mut() {
+            Some((line, group)) if *line == mark.span.line => group.push(mark),
+            _ => groups.push((mark.span.line, vec![mark])),
+        }
+    }
+    groups
+}
+
+/// The on-screen column of the `chars`th character of `line`, with tabs
+/// expanded the same way [`expand_tabs`] does.
+fn display_column(line: &str, chars: usize) -> usize {
+    let mut column = 0;
+    for ch in line.chars().take(chars) {
+        column += if ch == '\t' { TAB_WIDTH } else { 1 };
+    }
+    column + chars.saturating_sub(line.chars().count())
+}
+
+fn expand_tabs(line: &str) -> String {
+    line.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::code;
+
+    #[test]
+    fn renders_snippet_with_carets_and_labels() {
+        let source = "int main() {\n\tint x = 1;\n\tint x = 2;\n}\n";
+        let diagnostic = Diagnostic::error(code::DUPLICATE_DECLARATION, "`x` is declared twice")
+            .with_span(Span {
+                start: 30,
+                end: 31,
+                line: 3,
+                column: 6,
+            })
+            .with_label(
+                Span {
+                    start: 18,
+                    end: 19,
+                    line: 2,
+                    column: 6,
+                },
+                "first declared here",
+            )
+            .with_note("rename one of them");
+
+        assert_eq!(
+            Renderer::new(source, "main.c").render(&diagnostic),
+            "\
+error[E0200]: `x` is declared twice
+ --> main.c:3:6
+  |
+2 |     int x = 1;
+  |         - first declared here
+3 |     int x = 2;
+  |         ^
+  = note: rename one of them
+"
+        );
+    }
+
+    #[test]
+    fn renders_without_span_or_color_codes() {
+        let diagnostic = Diagnostic::error(code::MISSING_MAIN, "no main");
+        let plain = Renderer::new("", "a.c").render(&diagnostic);
+        assert_eq!(plain, "error[E0206]: no main\n --> a.c\n");
+        let colored = Renderer::new("", "a.c")
+            .with_color(true)
+            .render(&diagnostic);
+        assert!(colored.contains("\x1b[1;31merror[E0206]"));
+    }
+}
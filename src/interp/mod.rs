@@ -0,0 +1,566 @@
+//! A tree-walking interpreter that runs a checked [`Program`] from `main`.
+
+use std::{cmp::Ordering, collections::HashMap, io::Write, panic, thread};
+
+use crate::{
+    ast::{
+        BinaryOp, Block, Else, Expr, FunctionDecl, Ident, Item, Program, Stmt, UnaryOp, VarDecl,
+    },
+    diagnostic::{code, Diagnostic},
+    semantic::symbols::{SymbolId, SymbolTable},
+    token::Span,
+};
+
+mod value;
+
+pub use value::Value;
+
+/// A runtime error stops the program.
+type Result<T> = std::result::Result<T, Box<Diagnostic>>;
+
+/// How deep calls can be nested before the program is stopped.
+pub const MAX_CALL_DEPTH: usize = 1000;
+
+/// The stack of the thread programs run on. Every call of the program nests
+/// a few calls of the interpreter, this leaves room for [`MAX_CALL_DEPTH`]
+/// of them.
+const STACK_SIZE: usize = 64 << 20;
+
+/// How many of the innermost calls a runtime error lists before it skips to
+/// `main`.
+const SHOWN_CALLS: usize = 10;
+
+/// How a statement finished.
+enum Flow {
+    Next,
+    Break,
+    Continue,
+    Return(Value),
+}
+
+/// Runs `program`, writing what it prints to `out`, and returns what `main`
+/// returned. `program` has to have passed the semantic checks, with
+/// `symbols` the table they built.
+pub fn run(program: &Program, symbols: &SymbolTable, out: &mut (dyn Write + Send)) -> Result<i32> {
+    let run = || {
+        let mut interpreter = Interpreter {
+            symbols,
+            functions: HashMap::new(),
+            globals: HashMap::new(),
+            frames: vec![],
+            out,
+        };
+        interpreter
+            .start(program)
+            .map_err(|error| interpreter.with_call_stack(error))
+    };
+    thread::scope(|scope| {
+        thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn_scoped(scope, run)
+            .expect("cannot start the interpreter thread")
+            .join()
+            .unwrap_or_else(|payload| panic::resume_unwind(payload))
+    })
+}
+
+/// A running function.
+struct Frame<'a> {
+    function: &'a FunctionDecl,
+    /// Where it was called, `None` for `main`.
+    site: Option<Span>,
+    /// Its local variables and parameters. Names are resolved to symbols
+    /// already, so a frame needs no scopes of its own.
+    locals: HashMap<SymbolId, Value>,
+}
+
+struct Interpreter<'a> {
+    symbols: &'a SymbolTable,
+    functions: HashMap<SymbolId, &'a FunctionDecl>,
+    globals: HashMap<SymbolId, Value>,
+    /// The running functions, the innermost call last.
+    frames: Vec<Frame<'a>>,
+    out: &'a mut (dyn Write + Send),
+}
+
+impl<'a> Interpreter<'a> {
+    /// Initializes the globals in order, which can call the functions
+    /// declared before them, and then calls `main`.
+    fn start(&mut self, program: &'a Program) -> Result<i32> {
+        let mut main = None;
+        for item in &program.items {
+            match item {
+                Item::Function(function) => {
+                    let id = self.symbol(&function.name);
+                    self.functions.insert(id, function);
+                    if function.name.name == "main" {
+                        main = Some(function);
+                    }
+                }
+                Item::Var(var) => self.var_decl(var)?,
+            }
+        }
+        let main = main.expect("checked programs have a `main`");
+        Ok(self.call(main, vec![], None)?.as_int())
+    }
+
+    /// Calls `function` from `site`. When the call fails its frame is left
+    /// on the stack, so the error can be reported with every call that led
+    /// to it.
+    fn call(
+        &mut self,
+        function: &'a FunctionDecl,
+        args: Vec<Value>,
+        site: Option<Span>,
+    ) -> Result<Value> {
+        if self.frames.len() == MAX_CALL_DEPTH {
+            return Err(Box::new(
+                Diagnostic::error(
+                    code::STACK_OVERFLOW,
+                    format!("calling `{}` nests calls too deeply", function.name.name),
+                )
+                .with_span(site.unwrap_or(function.name.span))
+                .with_note(format!("calls can be nested at most {MAX_CALL_DEPTH} deep")),
+            ));
+        }
+        let locals = function
+            .params
+            .iter()
+            .map(|param| self.symbol(&param.name))
+            .zip(args)
+            .collect();
+        self.frames.push(Frame {
+            function,
+            site,
+            locals,
+        });
+        let flow = self.stmts(&function.body.stmts)?;
+        self.frames.pop();
+        match flow {
+            Flow::Return(value) => Ok(value),
+            _ => panic!("checked functions return on every path"),
+        }
+    }
+
+    fn var_decl(&mut self, var: &'a VarDecl) -> Result<()> {
+        let id = self.symbol(&var.name);
+        let value = match &var.init {
+            Some(init) => self.expr(init)?,
+            None => Value::zero(&self.symbols[id].ty),
+        };
+        if self.is_global(id) {
+            self.globals.insert(id, value);
+        } else {
+            self.frames
+                .last_mut()
+                .expect("locals are declared in a function")
+                .locals
+                .insert(id, value);
+        }
+        Ok(())
+    }
+
+    fn block(&mut self, block: &'a Block) -> Result<Flow> {
+        self.stmts(&block.stmts)
+    }
+
+    fn stmts(&mut self, stmts: &'a [Stmt]) -> Result<Flow> {
+        for stmt in stmts {
+            match self.stmt(stmt)? {
+                Flow::Next => {}
+                flow => return Ok(flow),
+            }
+        }
+        Ok(Flow::Next)
+    }
+
+    fn stmt(&mut self, stmt: &'a Stmt) -> Result<Flow> {
+        match stmt {
+            Stmt::Var(var) => self.var_decl(var)?,
+            Stmt::Assign { target, value, .. } => {
+                let value = self.expr(value)?;
+                let (name, indices) = self.place(target)?;
+                *self.element_mut(name, &indices)? = value;
+            }
+            Stmt::ExprStmt { expr, .. } => {
+                self.expr(expr)?;
+            }
+            Stmt::If {
+                cond,
+                then,
+                otherwise,
+                ..
+            } => {
+                if self.expr(cond)?.as_bool() {
+                    return self.block(then);
+                }
+                return match otherwise {
+                    Some(Else::If(stmt)) => self.stmt(stmt),
+                    Some(Else::Block(block)) => self.block(block),
+                    None => Ok(Flow::Next),
+                };
+            }
+            Stmt::For {
+                init,
+                cond,
+                update,
+                body,
+                ..
+            } => {
+                for stmt in init {
+                    self.stmt(stmt)?;
+                }
+                loop {
+                    if let Some(cond) = cond {
+                        if !self.expr(cond)?.as_bool() {
+                            break;
+                        }
+                    }
+                    match self.block(body)? {
+                        Flow::Next | Flow::Continue => {}
+                        Flow::Break => break,
+                        flow @ Flow::Return(_) => return Ok(flow),
+                    }
+                    if let Some(update) = update {
+                        self.stmt(update)?;
+                    }
+                }
+            }
+            Stmt::Return { value, .. } => return Ok(Flow::Return(self.expr(value)?)),
+            Stmt::Print { args, span } => {
+                for arg in args {
+                    let value = self.expr(arg)?;
+                    write!(self.out, "{value}").map_err(|err| {
+                        error(
+                            code::OUTPUT_FAILED,
+                            format!("cannot write the output: {err}"),
+                            *span,
+                        )
+                    })?;
+                }
+            }
+            Stmt::Break(_) => return Ok(Flow::Break),
+            Stmt::Continue(_) => return Ok(Flow::Continue),
+        }
+        Ok(Flow::Next)
+    }
+
+    fn expr(&mut self, expr: &'a Expr) -> Result<Value> {
+        match expr {
+            Expr::Literal(literal, _) => Ok(Value::of_literal(literal)),
+            Expr::Var(_) | Expr::Index { .. } => {
+                let (name, indices) = self.place(expr)?;
+                Ok(self.element_mut(name, &indices)?.clone())
+            }
+            Expr::Call {
+                callee, args, span, ..
+            } => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.expr(arg))
+                    .collect::<Result<Vec<_>>>()?;
+                let function = self.functions[&self.symbol(callee)];
+                self.call(function, args, Some(*span))
+            }
+            Expr::Unary {
+                op: UnaryOp::Not,
+                operand,
+                ..
+            } => Ok(Value::Bool(!self.expr(operand)?.as_bool())),
+            Expr::Binary {
+                op: BinaryOp::And,
+                lhs,
+                rhs,
+                ..
+            } => Ok(Value::Bool(
+                self.expr(lhs)?.as_bool() && self.expr(rhs)?.as_bool(),
+            )),
+            Expr::Binary {
+                op: BinaryOp::Or,
+                lhs,
+                rhs,
+                ..
+            } => Ok(Value::Bool(
+                self.expr(lhs)?.as_bool() || self.expr(rhs)?.as_bool(),
+            )),
+            Expr::Binary {
+                op, lhs, rhs, span, ..
+            } => {
+                let lhs = self.expr(lhs)?;
+                let rhs = self.expr(rhs)?;
+                binary(*op, &lhs, &rhs, *span)
+            }
+            Expr::Error(_) => panic!("checked programs have no errors"),
+        }
+    }
+
+    /// The variable an assignment target or a read names, with the value
+    /// and span of each index applied to it, outermost first.
+    fn place(&mut self, expr: &'a Expr) -> Result<(&'a Ident, Vec<(i32, Span)>)> {
+        let mut indices = vec![];
+        let mut base = expr;
+        while let Expr::Index { array, index, .. } = base {
+            indices.push(index);
+            base = array;
+        }
+        let Expr::Var(name) = base else {
+            panic!("only variables can be indexed");
+        };
+        let indices = indices
+            .into_iter()
+            .rev()
+            .map(|index| Ok((self.expr(index)?.as_int(), index.span())))
+            .collect::<Result<_>>()?;
+        Ok((name, indices))
+    }
+
+    /// The value of `name` with `indices` applied to it, which have to be
+    /// within the bounds of the array.
+    fn element_mut(&mut self, name: &Ident, indices: &[(i32, Span)]) -> Result<&mut Value> {
+        let id = self.symbol(name);
+        let mut value = if self.is_global(id) {
+            self.globals.get_mut(&id)
+        } else {
+            self.frames
+                .last_mut()
+                .and_then(|frame| frame.locals.get_mut(&id))
+        }
+        .expect("variables are declared before they are used");
+        for &(index, span) in indices {
+            let Value::Array(elements) = value else {
+                panic!("only arrays can be indexed");
+            };
+            let len = elements.len();
+            value = usize::try_from(index)
+                .ok()
+                .and_then(|index| elements.get_mut(index))
+                .ok_or_else(|| {
+                    error(
+                        code::INDEX_OUT_OF_BOUNDS,
+                        format!(
+                            "index {index} is out of bounds for `{}` of length {len}",
+                            name.name
+                        ),
+                        span,
+                    )
+                })?;
+        }
+        Ok(value)
+    }
+
+    /// The symbol `name` was resolved to, or declares.
+    fn symbol(&self, name: &Ident) -> SymbolId {
+        self.symbols
+            .reference(name.span)
+            .expect("checked names are resolved")
+    }
+
+    fn is_global(&self, id: SymbolId) -> bool {
+        self.symbols[id].scope == self.symbols.global()
+    }
+
+    /// Notes the calls that were running when `error` stopped the program,
+    /// innermost first.
+    fn with_call_stack(&self, mut error: Box<Diagnostic>) -> Box<Diagnostic> {
+        let frames = &self.frames;
+        for (i, frame) in frames.iter().rev().enumerate() {
+            let name = &frame.function.name.name;
+            let note = match frame.site {
+                Some(site) => format!("in `{name}`, called from line {}", site.line),
+                None => format!("in `{name}`"),
+            };
+            if i < SHOWN_CALLS || i + 1 == frames.len() {
+                error.notes.push(note);
+            } else if i == SHOWN_CALLS {
+                let skipped = frames.len() - SHOWN_CALLS - 1;
+                error.notes.push(format!(
+                    "... {skipped} more call{}",
+                    if skipped == 1 { "" } else { "s" }
+                ));
+            }
+        }
+        error
+    }
+}
+
+fn error(code: &'static str, message: String, span: Span) -> Box<Diagnostic> {
+    Box::new(Diagnostic::error(code, message).with_span(span))
+}
+
+/// Applies an operator other than `&&` and `||`, which short-circuit.
+fn binary(op: BinaryOp, lhs: &Value, rhs: &Value, span: Span) -> Result<Value> {
+    let ordering = || match (lhs, rhs) {
+        (Value::Char(lhs), Value::Char(rhs)) => lhs.cmp(rhs),
+        _ => lhs.as_int().cmp(&rhs.as_int()),
+    };
+    Ok(match op {
+        BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => {
+            let (lhs, rhs) = (lhs.as_int(), rhs.as_int());
+            let result = match op {
+                BinaryOp::Add => lhs.checked_add(rhs),
+                BinaryOp::Sub => lhs.checked_sub(rhs),
+                BinaryOp::Mul => lhs.checked_mul(rhs),
+                _ if rhs == 0 => {
+                    let message = if op == BinaryOp::Div {
+                        format!("attempt to divide `{lhs}` by zero")
+                    } else {
+                        format!(
+                            "attempt to calculate the remainder of `{lhs}` with a divisor of zero"
+                        )
+                    };
+                    return Err(error(code::DIVISION_BY_ZERO, message, span));
+                }
+                BinaryOp::Div => lhs.checked_div(rhs),
+                _ => lhs.checked_rem(rhs),
+            };
+            let result = result.ok_or_else(|| {
+                Box::new(
+                    Diagnostic::error(
+                        code::INTEGER_OVERFLOW,
+                        format!("`{lhs} {op} {rhs}` overflows `int`"),
+                    )
+                    .with_span(span)
+                    .with_note(format!(
+                        "an `int` is between {} and {}",
+                        i32::MIN,
+                        i32::MAX
+                    )),
+                )
+            })?;
+            Value::Int(result)
+        }
+        BinaryOp::Eq => Value::Bool(lhs == rhs),
+        BinaryOp::Ne => Value::Bool(lhs != rhs),
+        BinaryOp::Lt => Value::Bool(ordering() == Ordering::Less),
+        BinaryOp::Le => Value::Bool(ordering() != Ordering::Greater),
+        BinaryOp::Gt => Value::Bool(ordering() == Ordering::Greater),
+        BinaryOp::Ge => Value::Bool(ordering() != Ordering::Less),
+        BinaryOp::And | BinaryOp::Or => unreachable!("short-circuits"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_CALL_DEPTH, SHOWN_CALLS};
+    use crate::diagnostic::{code, Lints};
+
+    /// What the program prints, what `main` returns and the codes of the
+    /// errors.
+    fn run(source: &str) -> (String, Option<i32>, Vec<&'static str>) {
+        let mut out = vec![];
+        let ran = crate::run(source, Lints::allow_all(), &mut out);
+        (
+            String::from_utf8(out).unwrap(),
+            ran.exit_code,
+            ran.diagnostics.codes(),
+        )
+    }
+
+    #[test]
+    fn runs_functions_loops_and_arrays() {
+        let (out, exit_code, errors) = run("int calls;\n\
+             int fact(int n) {\n\
+             \x20 calls = calls + 1;\n\
+             \x20 if (n <= 1) { return 1; }\n\
+             \x20 return n * fact(n - 1);\n\
+             }\n\
+             int main() {\n\
+             \x20 int m[2][3];\n\
+             \x20 for (int i = 0; i < 2; i = i + 1) {\n\
+             \x20   for (int j = 0; ; j = j + 1) {\n\
+             \x20     if (j == 3) { break; } else if (j == 1) { continue; }\n\
+             \x20     m[i][j] = i * 10 + j;\n\
+             \x20   }\n\
+             \x20 }\n\
+             \x20 print(\"fact(5) = \", fact(5), \", calls = \", calls, \"\\n\");\n\
+             \x20 print(m[1][2], ' ', m[1][1], ' ', true && !false, \"\\n\");\n\
+             \x20 char c = 'a';\n\
+             \x20 if (c < 'b') { int c = 7; print(c, \"\\n\"); }\n\
+             \x20 print(c);\n\
+             \x20 return 0x2A;\n\
+             }");
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(out, "fact(5) = 120, calls = 5\n12 0 true\n7\na");
+        assert_eq!(exit_code, Some(42));
+    }
+
+    #[test]
+    fn short_circuits_and_stops_at_runtime_errors() {
+        let (out, exit_code, errors) = run("bool loud(bool b) { print(b); return b; }\n\
+             int main() {\n\
+             \x20 bool b = loud(false) && loud(true) || loud(true);\n\
+             \x20 int zero;\n\
+             \x20 zero = 0;\n\
+             \x20 return 1 % zero;\n\
+             }");
+        assert_eq!((out.as_str(), exit_code), ("falsetrue", None));
+        assert_eq!(errors, [code::DIVISION_BY_ZERO]);
+
+        let (_, exit_code, errors) = run("int main() { int x = 1; print(x); x = ; return x; }");
+        assert_eq!(exit_code, None);
+        assert_eq!(errors, [code::UNEXPECTED_TOKEN]);
+    }
+
+    /// The message, notes and line of the runtime error of `source`.
+    fn runtime_error(source: &str) -> (String, Vec<String>, usize) {
+        let mut out = vec![];
+        let ran = crate::run(source, Lints::allow_all(), &mut out);
+        assert_eq!(ran.exit_code, None);
+        let error = ran.diagnostics.into_vec().pop().unwrap();
+        (error.message, error.notes, error.span.unwrap().line)
+    }
+
+    #[test]
+    fn reports_runtime_errors_with_the_call_stack() {
+        let (message, notes, line) = runtime_error(
+            "int at(int i) { int a[4]; return a[i]; }\n\
+             int down(int n) {\n\
+             \x20 if (n == 0) { return 7 % n; }\n\
+             \x20 return down(n - 1);\n\
+             }\n\
+             int main() {\n\
+             \x20 print(at(3));\n\
+             \x20 return down(2);\n\
+             }",
+        );
+        assert_eq!(
+            message,
+            "attempt to calculate the remainder of `7` with a divisor of zero"
+        );
+        assert_eq!(line, 3);
+        assert_eq!(
+            notes,
+            [
+                "in `down`, called from line 4",
+                "in `down`, called from line 4",
+                "in `down`, called from line 8",
+                "in `main`"
+            ]
+        );
+
+        let (message, _, line) = runtime_error(
+            "int at(int i) { int a[4]; return a[i]; }\nint main() { return at(0 - 1); }",
+        );
+        assert_eq!(
+            (message.as_str(), line),
+            ("index -1 is out of bounds for `a` of length 4", 1)
+        );
+
+        let (message, notes, _) =
+            runtime_error("int g;\nint main() { g = 2147483647; return g + 1; }");
+        assert_eq!(message, "`2147483647 + 1` overflows `int`");
+        assert_eq!(notes.last().unwrap(), "in `main`");
+    }
+
+    #[test]
+    fn stops_deep_recursion() {
+        let (message, notes, _) =
+            runtime_error("int f(int n) { return f(n + 1); }\nint main() { return f(0); }");
+        assert_eq!(message, "calling `f` nests calls too deeply");
+        assert_eq!(notes.len(), 1 + SHOWN_CALLS + 2);
+        assert_eq!(
+            notes[SHOWN_CALLS + 1],
+            format!("... {} more calls", MAX_CALL_DEPTH - SHOWN_CALLS - 1)
+        );
+    }
+}
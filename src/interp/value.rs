@@ -0,0 +1,68 @@
+use std::fmt;
+
+use crate::{ast::Literal, semantic::types::Type};
+
+/// A value of the running program. The checker makes sure every operation
+/// gets values of the types it expects.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i32),
+    Bool(bool),
+    Char(char),
+    /// A string literal, the only place strings appear.
+    String(String),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    /// The value a variable of type `ty` starts out with: `0`, `false`,
+    /// `'\0'`, or an array of those.
+    pub fn zero(ty: &Type) -> Value {
+        match ty {
+            Type::Bool => Value::Bool(false),
+            Type::Char => Value::Char('\0'),
+            Type::Array(element, len) => Value::Array(vec![Value::zero(element); *len]),
+            _ => Value::Int(0),
+        }
+    }
+
+    pub fn of_literal(literal: &Literal) -> Value {
+        match literal {
+            Literal::Int(n) => Value::Int(*n),
+            Literal::Bool(b) => Value::Bool(*b),
+            Literal::Char(c) => Value::Char(*c),
+            Literal::String(s) => Value::String(s.clone()),
+        }
+    }
+
+    pub fn as_int(&self) -> i32 {
+        match self {
+            Value::Int(n) => *n,
+            other => panic!("expected an `int`, found {other:?}"),
+        }
+    }
+
+    pub fn as_bool(&self) -> bool {
+        match self {
+            Value::Bool(b) => *b,
+            other => panic!("expected a `bool`, found {other:?}"),
+        }
+    }
+}
+
+/// How `print` writes the value: numbers in decimal, `true`/`false`, and
+/// characters and strings as they are.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(n) => write!(f, "{n}"),
+            Value::Bool(b) => write!(f, "{b}"),
+            Value::Char(c) => write!(f, "{c}"),
+            Value::String(s) => f.write_str(s),
+            Value::Array(elements) => {
+                let elements: Vec<String> = elements.iter().map(ToString::to_string).collect();
+                write!(f, "{{{}}}", elements.join(", "))
+            }
+        }
+    }
+}
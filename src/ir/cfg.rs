@@ -0,0 +1,321 @@
+//! Control-flow graphs of the functions of the IR.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Write},
+};
+
+use super::{Function, Instr, Label, Module};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BlockId(pub usize);
+
+/// A straight run of instructions that is only entered at the top and only
+/// left at the bottom.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    pub label: Label,
+    /// Without the label, ending in a jump, a branch or a `return`. Only a
+    /// block that control never reaches the end of, like the end of a
+    /// function whose every path returns earlier, has no such last
+    /// instruction and no successors.
+    pub instrs: Vec<Instr>,
+    pub preds: Vec<BlockId>,
+    pub succs: Vec<BlockId>,
+}
+
+impl Block {
+    fn new(label: Label) -> Self {
+        Self {
+            label,
+            instrs: vec![],
+            preds: vec![],
+            succs: vec![],
+        }
+    }
+
+    fn is_terminated(&self) -> bool {
+        self.instrs.last().is_some_and(Instr::is_terminator)
+    }
+}
+
+/// The basic blocks of a function and the edges between them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cfg {
+    /// In the order of the function body, starting with the entry block,
+    /// which has no predecessors.
+    pub blocks: Vec<Block>,
+    /// How many labels the blocks use, like [`Function::labels`]. Code that
+    /// does not start with a label gets a new one.
+    pub labels: usize,
+}
+
+impl Cfg {
+    pub const ENTRY: BlockId = BlockId(0);
+
+    /// Splits the body of `function` into blocks. A block that runs on into
+    /// the next one gets an explicit jump to it.
+    pub fn new(function: &Function) -> Self {
+        let mut cfg = Cfg {
+            blocks: vec![],
+            labels: function.labels,
+        };
+        // Not a label of the body, so no jump goes to it even when the body
+        // starts with a loop.
+        cfg.new_block();
+        for instr in &function.body {
+            match instr {
+                Instr::Label(label) => {
+                    let last = cfg.blocks.last_mut().expect("there is an entry block");
+                    if !last.is_terminated() {
+                        last.instrs.push(Instr::Jump(*label));
+                    }
+                    cfg.blocks.push(Block::new(*label));
+                }
+                instr => {
+                    if cfg.blocks.last().is_some_and(Block::is_terminated) {
+                        cfg.new_block();
+                    }
+                    let last = cfg.blocks.last_mut().expect("there is an entry block");
+                    last.instrs.push(instr.clone());
+                }
+            }
+        }
+        cfg.connect();
+        cfg
+    }
+
+    /// Adds an empty block with a new label at the end.
+    pub fn new_block(&mut self) -> BlockId {
+        self.blocks.push(Block::new(Label(self.labels)));
+        self.labels += 1;
+        BlockId(self.blocks.len() - 1)
+    }
+
+    /// The block that starts with `label`.
+    pub fn block_of(&self, label: Label) -> Option<BlockId> {
+        self.blocks
+            .iter()
+            .position(|block| block.label == label)
+            .map(BlockId)
+    }
+
+    /// Recomputes the predecessors and successors of every block from the
+    /// jumps and branches that end them.
+    pub fn connect(&mut self) {
+        let blocks: HashMap<Label, BlockId> = self
+            .blocks
+            .iter()
+            .enumerate()
+            .map(|(i, block)| (block.label, BlockId(i)))
+            .collect();
+        for block in &mut self.blocks {
+            block.preds.clear();
+            block.succs.clear();
+        }
+        for i in 0..self.blocks.len() {
+            let targets = self.blocks[i]
+                .instrs
+                .last()
+                .map(Instr::targets)
+                .unwrap_or_default();
+            for target in targets {
+                let succ = blocks[&target];
+                if !self.blocks[i].succs.contains(&succ) {
+                    self.blocks[i].succs.push(succ);
+                    self.blocks[succ.0].preds.push(BlockId(i));
+                }
+            }
+        }
+    }
+
+    /// Replaces the body of `function` with the blocks, in order. A jump to
+    /// the block right after is left out, and so is the label of the entry
+    /// block, which nothing jumps to, unless a [phi](Instr::Phi) names it.
+    pub fn write_to(&self, function: &mut Function) {
+        let entry = self.blocks[Cfg::ENTRY.0].label;
+        let named = self.blocks.iter().flat_map(|block| &block.instrs).any(|instr| {
+            matches!(instr, Instr::Phi { args, .. } if args.iter().any(|(label, _)| *label == entry))
+        });
+        let mut body = vec![];
+        for (i, block) in self.blocks.iter().enumerate() {
+            if i > 0 || named {
+                body.push(Instr::Label(block.label));
+            }
+            let next = self.blocks.get(i + 1).map(|next| next.label);
+            match block.instrs.split_last() {
+                Some((Instr::Jump(target), rest)) if Some(*target) == next => {
+                    body.extend_from_slice(rest)
+                }
+                _ => body.extend_from_slice(&block.instrs),
+            }
+        }
+        function.body = body;
+        function.labels = self.labels;
+    }
+
+    /// Removes the blocks that cannot be reached from the entry, returning
+    /// whether there were any.
+    pub fn remove_unreachable(&mut self) -> bool {
+        let reachable = self.reachable();
+        if !reachable.contains(&false) {
+            return false;
+        }
+        let mut reachable = reachable.into_iter();
+        self.blocks.retain(|_| reachable.next().unwrap());
+        self.connect();
+        true
+    }
+
+    /// Whether each block can be reached from the entry block.
+    pub fn reachable(&self) -> Vec<bool> {
+        let mut reachable = vec![false; self.blocks.len()];
+        let mut stack = vec![Cfg::ENTRY];
+        while let Some(block) = stack.pop() {
+            if !reachable[block.0] {
+                reachable[block.0] = true;
+                stack.extend(&self.blocks[block.0].succs);
+            }
+        }
+        reachable
+    }
+
+    /// Writes the graph as a Graphviz cluster named after `function`, with
+    /// blocks that cannot be reached dashed. `module` names the globals.
+    fn write_dot(&self, f: &mut impl Write, function: &Function, module: &Module) -> fmt::Result {
+        let node = |block: BlockId| format!("\"{}.{}\"", function.name, self.blocks[block.0].label);
+        writeln!(f, "    subgraph \"cluster_{}\" {{", function.name)?;
+        writeln!(f, "        label=\"{}\";", function.name)?;
+        let reachable = self.reachable();
+        for (i, block) in self.blocks.iter().enumerate() {
+            let mut text = format!("{}:\\l", block.label);
+            for instr in &block.instrs {
+                let instr = function.display(instr, module).to_string();
+                write!(text, "    {}\\l", escape(&instr))?;
+            }
+            let style = if reachable[i] { "" } else { ", style=dashed" };
+            writeln!(f, "        {} [label=\"{text}\"{style}];", node(BlockId(i)))?;
+        }
+        for (i, block) in self.blocks.iter().enumerate() {
+            let branch = matches!(block.instrs.last(), Some(Instr::Branch { .. }));
+            for (j, &succ) in block.succs.iter().enumerate() {
+                write!(f, "        {} -> {}", node(BlockId(i)), node(succ))?;
+                match j {
+                    _ if !branch => writeln!(f, ";")?,
+                    0 => writeln!(f, " [label=\"true\"];")?,
+                    _ => writeln!(f, " [label=\"false\"];")?,
+                }
+            }
+        }
+        writeln!(f, "    }}")
+    }
+}
+
+/// The control-flow graphs of every function of `module` as one Graphviz
+/// graph, with a cluster per function.
+pub fn to_dot(module: &Module) -> String {
+    let mut dot = String::from("digraph cfg {\n    node [shape=box, fontname=\"monospace\"];\n");
+    for function in &module.functions {
+        Cfg::new(function)
+            .write_dot(&mut dot, function, module)
+            .expect("writing to a string cannot fail");
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// `text` in a quoted Graphviz string.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{opt::OptLevel, test_module};
+
+    /// The successors of every block, by label.
+    fn edges(cfg: &Cfg) -> Vec<(usize, Vec<usize>)> {
+        cfg.blocks
+            .iter()
+            .map(|block| {
+                let succs = block
+                    .succs
+                    .iter()
+                    .map(|succ| cfg.blocks[succ.0].label.0)
+                    .collect();
+                (block.label.0, succs)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn splits_functions_into_connected_blocks() {
+        let module = test_module(
+            "int f(int n) {\n\
+             \x20 for (int i = 0; ; i = i + 1) {\n\
+             \x20   if (i == n) { break; } else if (i > 9) { return 0; }\n\
+             \x20   continue;\n\
+             \x20 }\n\
+             \x20 return n;\n\
+             }\n\
+             int main() { for (;;) { return f(1); } }",
+            OptLevel::O0,
+        );
+        let f = Cfg::new(&module.functions[0]);
+        assert_eq!(
+            edges(&f),
+            [
+                (8, vec![0]),
+                (0, vec![1, 2]),
+                (1, vec![7]),
+                (2, vec![3, 4]),
+                (3, vec![]),
+                (4, vec![5]),
+                (5, vec![6]),
+                (6, vec![0]),
+                (7, vec![]),
+            ]
+        );
+        assert_eq!(f.blocks[1].preds, [BlockId(0), BlockId(7)]);
+        assert_eq!(f.blocks[0].instrs.last(), Some(&Instr::Jump(Label(0))));
+
+        // The end of `main` is never reached.
+        let main = Cfg::new(&module.functions[1]);
+        assert_eq!(
+            edges(&main),
+            [(3, vec![0]), (0, vec![]), (1, vec![0]), (2, vec![])]
+        );
+        assert_eq!(main.reachable(), [true, true, false, false]);
+    }
+
+    #[test]
+    fn exports_graphviz_clusters() {
+        let module = test_module(
+            "int main() {\n\
+             \x20 bool b = true;\n\
+             \x20 if (b) { print(\"\\\"hi\\\"\\n\"); }\n\
+             \x20 return 0;\n\
+             \x20 print(1);\n\
+             }",
+            OptLevel::O0,
+        );
+        assert_eq!(
+            to_dot(&module),
+            r#"digraph cfg {
+    node [shape=box, fontname="monospace"];
+    subgraph "cluster_main" {
+        label="main";
+        "main.L2" [label="L2:\l    b = 1\l    if b goto L0 else goto L1\l"];
+        "main.L0" [label="L0:\l    print \"\\\"hi\\\"\\n\"\l    goto L1\l"];
+        "main.L1" [label="L1:\l    return 0\l"];
+        "main.L3" [label="L3:\l    print int 1\l", style=dashed];
+        "main.L2" -> "main.L0" [label="true"];
+        "main.L2" -> "main.L1" [label="false"];
+        "main.L0" -> "main.L1";
+    }
+}
+"#
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! Copy propagation on [SSA form](super::ssa).
+//!
+//! Every variable is written once, so a copy `x = y` means `x` is `y`
+//! everywhere it is read: the reads of `x` can read `y` instead and the
+//! copy goes. A [phi](Instr::Phi) whose arguments are all the same value,
+//! or the phi itself around a loop, is a copy of that value too.
+
+use super::{Function, Instr, Operand, VarId};
+
+/// Replaces the reads of every copy of `function` with what it copies and
+/// removes the copies, returning whether anything changed.
+pub fn propagate_copies(function: &mut Function) -> bool {
+    // What every variable that is a copy copies.
+    let mut copies: Vec<Option<Operand>> = vec![None; function.vars.len()];
+    for instr in &function.body {
+        match instr {
+            Instr::Copy { dst, src } if *src != Operand::Var(*dst) => copies[dst.0] = Some(*src),
+            Instr::Phi { dst, args } => {
+                let mut values = args
+                    .iter()
+                    .map(|(_, value)| *value)
+                    .filter(|value| *value != Operand::Var(*dst));
+                if let Some(first) = values.next() {
+                    if values.all(|value| value == first) {
+                        copies[dst.0] = Some(first);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    // Follows chains of copies to the value at their start. A cycle, which
+    // only blocks that cannot be reached can make, is left alone.
+    let resolve = |var: VarId| {
+        let mut value = Operand::Var(var);
+        for _ in 0..copies.len() {
+            match value {
+                Operand::Var(var) => match copies[var.0] {
+                    Some(copied) => value = copied,
+                    None => return Some(value),
+                },
+                Operand::Const(_) => return Some(value),
+            }
+        }
+        None
+    };
+    let resolved: Vec<Option<Operand>> = (0..copies.len())
+        .map(|var| copies[var].and_then(|_| resolve(VarId(var))))
+        .collect();
+    if resolved.iter().all(Option::is_none) {
+        return false;
+    }
+    function.body.retain(|instr| match instr {
+        Instr::Copy { dst, .. } | Instr::Phi { dst, .. } => resolved[dst.0].is_none(),
+        _ => true,
+    });
+    for operand in function.body.iter_mut().flat_map(Instr::uses_mut) {
+        if let Operand::Var(var) = operand {
+            if let Some(value) = resolved[var.0] {
+                *operand = value;
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{opt::OptLevel, ssa::into_ssa, test_ir, test_module, verify::verify};
+
+    #[test]
+    fn reads_what_copies_copy() {
+        let module = test_module(
+            "int f(int n) {\n\
+             \x20 int k = n;\n\
+             \x20 int sum = 0;\n\
+             \x20 for (int i = 0; i < 3; i = i + 1) { k = n; sum = sum + k; }\n\
+             \x20 return sum + k;\n\
+             }\n\
+             int main() { return f(2); }",
+            OptLevel::O1,
+        );
+        let mut f = module.functions[0].clone();
+        into_ssa(&mut f);
+        // The copies of `k` are `n`, and `sum` and `i` start out as `0`.
+        assert!(propagate_copies(&mut f));
+        assert_eq!(verify(&f, true), Ok(()));
+        assert_eq!(
+            test_ir(&f, &module),
+            "function f(n) {\n\
+             L0:\n\
+             L1:\n\
+             \x20   k.1 = phi [n, L0], [n, L2]\n\
+             \x20   sum.1 = phi [0, L0], [sum.2, L2]\n\
+             \x20   i.1 = phi [0, L0], [i.2, L2]\n\
+             \x20   t0 = i.1 < 3\n\
+             \x20   if t0 goto L2 else goto L3\n\
+             L2:\n\
+             \x20   sum.2 = sum.1 + n\n\
+             \x20   i.2 = i.1 + 1\n\
+             \x20   goto L1\n\
+             L3:\n\
+             \x20   t1 = sum.1 + k.1\n\
+             \x20   return t1\n\
+             }\n"
+        );
+        // So the phi of `k` only ever picks `n`.
+        assert!(propagate_copies(&mut f));
+        assert_eq!(
+            test_ir(&f, &module),
+            "function f(n) {\n\
+             L0:\n\
+             L1:\n\
+             \x20   sum.1 = phi [0, L0], [sum.2, L2]\n\
+             \x20   i.1 = phi [0, L0], [i.2, L2]\n\
+             \x20   t0 = i.1 < 3\n\
+             \x20   if t0 goto L2 else goto L3\n\
+             L2:\n\
+             \x20   sum.2 = sum.1 + n\n\
+             \x20   i.2 = i.1 + 1\n\
+             \x20   goto L1\n\
+             L3:\n\
+             \x20   t1 = sum.1 + n\n\
+             \x20   return t1\n\
+             }\n"
+        );
+        assert!(!propagate_copies(&mut f));
+    }
+}
@@ -0,0 +1,156 @@
+//! Dead code elimination.
+//!
+//! Removes the instructions that write a variable no later instruction
+//! reads, on any path, before it is written again. Only instructions that
+//! do nothing else are removed: a call stays, without its result, and so
+//! does arithmetic that might stop the program by dividing by zero or
+//! overflowing. Which variables are live is found by [`Liveness`].
+
+use crate::semantic::consts;
+
+use super::{
+    cfg::{Block, Cfg},
+    liveness::{read, Liveness},
+    BinOp, Function, Instr, Operand,
+};
+
+/// Removes the dead assignments of `function`, returning whether anything
+/// changed.
+pub fn eliminate_dead_code(function: &mut Function) -> bool {
+    let mut cfg = Cfg::new(function);
+    let Liveness { live_out, .. } = Liveness::new(&cfg, function.vars.len());
+    let mut changed = false;
+    for (block, live) in cfg.blocks.iter_mut().zip(live_out) {
+        changed |= sweep(block, live);
+    }
+    if changed {
+        cfg.write_to(function);
+    }
+    changed
+}
+
+/// Removes the dead assignments of `block`, with `live` the variables that
+/// are live at its end.
+fn sweep(block: &mut Block, mut live: Vec<bool>) -> bool {
+    let mut changed = false;
+    let mut instrs = Vec::with_capacity(block.instrs.len());
+    for mut instr in block.instrs.drain(..).rev() {
+        if let Instr::Copy {
+            dst,
+            src: Operand::Var(src),
+        } = instr
+        {
+            if dst == src {
+                changed = true;
+                continue;
+            }
+        }
+        if let Some(dst) = instr.def() {
+            if !live[dst.0] {
+                match &mut instr {
+                    Instr::Call { dst, .. } => {
+                        *dst = None;
+                        changed = true;
+                    }
+                    instr if !has_effect(instr) => {
+                        changed = true;
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+            live[dst.0] = false;
+        }
+        for var in read(&instr) {
+            live[var] = true;
+        }
+        instrs.push(instr);
+    }
+    instrs.reverse();
+    block.instrs = instrs;
+    changed
+}
+
+/// Whether `instr` does more than write its result.
+pub fn has_effect(instr: &Instr) -> bool {
+    match instr {
+        Instr::Binary { op, lhs, rhs, .. } => might_fail(*op, *lhs, *rhs),
+        Instr::Copy { .. }
+        | Instr::Unary { .. }
+        | Instr::GetGlobal { .. }
+        | Instr::Load { .. }
+        | Instr::Phi { .. } => false,
+        _ => true,
+    }
+}
+
+/// Whether `lhs op rhs` might stop the program with a runtime error.
+fn might_fail(op: BinOp, lhs: Operand, rhs: Operand) -> bool {
+    match (op, lhs, rhs) {
+        (
+            BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Rem,
+            Operand::Const(lhs),
+            Operand::Const(rhs),
+        ) => consts::binary(op, lhs, rhs).is_none(),
+        // Only `i32::MIN / -1` overflows.
+        (BinOp::Div | BinOp::Rem, _, Operand::Const(rhs)) => rhs == 0 || rhs == -1,
+        (BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Rem, _, _) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{opt::OptLevel, test_ir, test_module};
+
+    #[test]
+    fn removes_assignments_that_are_never_read() {
+        let module = test_module(
+            "int g;\n\
+             int f(int n) { g = g + n; return g; }\n\
+             int main() {\n\
+             \x20 int zero;\n\
+             \x20 zero = 0;\n\
+             \x20 int unused = 2 * 8;\n\
+             \x20 int x = f(1);\n\
+             \x20 int y = x / zero;\n\
+             \x20 int z = x + 1;\n\
+             \x20 bool b = z > 3 && x < 9;\n\
+             \x20 z = f(3);\n\
+             \x20 return x * 2;\n\
+             }",
+            OptLevel::O0,
+        );
+        let mut main = module.functions[1].clone();
+        assert!(eliminate_dead_code(&mut main));
+        let ir = test_ir(&main, &module);
+        // The call to `f` stays for what it does to `g`, and the division and
+        // the addition for the runtime errors they might stop the program
+        // with. The branches are left for `simplify_cfg`.
+        assert_eq!(
+            ir,
+            "function main() {\n\
+             \x20   zero = 0\n\
+             \x20   param 1\n\
+             \x20   x = call f, 1\n\
+             \x20   y = x / zero\n\
+             \x20   z = x + 1\n\
+             \x20   t0 = z > 3\n\
+             \x20   if t0 goto L0 else goto L2\n\
+             L0:\n\
+             \x20   t1 = x < 9\n\
+             \x20   if t1 goto L1 else goto L2\n\
+             L1:\n\
+             \x20   goto L3\n\
+             L2:\n\
+             L3:\n\
+             \x20   param 3\n\
+             \x20   call f, 1\n\
+             \x20   t2 = x * 2\n\
+             \x20   return t2\n\
+             }\n"
+        );
+        assert!(!eliminate_dead_code(&mut main));
+    }
+}
@@ -0,0 +1,224 @@
+//! Dominators, with the algorithm of Cooper, Harvey and Kennedy, "A Simple,
+//! Fast Dominance Algorithm".
+//!
+//! A block dominates another when every path from the entry to the other
+//! goes through it. Every block dominates itself, and the blocks that
+//! dominate a block form a chain from the entry down to its immediate
+//! dominator, so they make a tree rooted at the entry.
+
+use super::cfg::{BlockId, Cfg};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dominators {
+    /// The immediate dominator of every block, `None` for the entry and the
+    /// blocks that cannot be reached.
+    idom: Vec<Option<BlockId>>,
+    children: Vec<Vec<BlockId>>,
+    /// When each block is entered and left by a walk of the tree, to tell
+    /// dominance in constant time.
+    enter: Vec<usize>,
+    leave: Vec<usize>,
+}
+
+impl Dominators {
+    pub fn new(cfg: &Cfg) -> Self {
+        let order = reverse_postorder(cfg);
+        // The position of every block in `order`, `None` for the blocks
+        // that cannot be reached.
+        let mut position = vec![None; cfg.blocks.len()];
+        for (i, block) in order.iter().enumerate() {
+            position[block.0] = Some(i);
+        }
+        let mut idom: Vec<Option<BlockId>> = vec![None; cfg.blocks.len()];
+        idom[Cfg::ENTRY.0] = Some(Cfg::ENTRY);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &block in &order[1..] {
+                let mut preds = cfg.blocks[block.0]
+                    .preds
+                    .iter()
+                    .copied()
+                    .filter(|pred| idom[pred.0].is_some());
+                let Some(first) = preds.next() else {
+                    continue;
+                };
+                let new = preds.fold(first, |mut a, mut b| {
+                    // Walks up from both until they meet.
+                    while a != b {
+                        while position[a.0] > position[b.0] {
+                            a = idom[a.0].expect("processed");
+                        }
+                        while position[b.0] > position[a.0] {
+                            b = idom[b.0].expect("processed");
+                        }
+                    }
+                    a
+                });
+                if idom[block.0] != Some(new) {
+                    idom[block.0] = Some(new);
+                    changed = true;
+                }
+            }
+        }
+        idom[Cfg::ENTRY.0] = None;
+
+        let mut children = vec![vec![]; cfg.blocks.len()];
+        for (i, parent) in idom.iter().enumerate() {
+            if let Some(parent) = parent {
+                children[parent.0].push(BlockId(i));
+            }
+        }
+        let mut dominators = Dominators {
+            idom,
+            children,
+            enter: vec![usize::MAX; cfg.blocks.len()],
+            leave: vec![usize::MAX; cfg.blocks.len()],
+        };
+        let mut clock = 0;
+        let mut stack = vec![(Cfg::ENTRY, false)];
+        while let Some((block, left)) = stack.pop() {
+            clock += 1;
+            if left {
+                dominators.leave[block.0] = clock;
+                continue;
+            }
+            dominators.enter[block.0] = clock;
+            stack.push((block, true));
+            for &child in dominators.children[block.0].iter().rev() {
+                stack.push((child, false));
+            }
+        }
+        dominators
+    }
+
+    /// The immediate dominator of `block`, `None` for the entry and the
+    /// blocks that cannot be reached.
+    pub fn idom(&self, block: BlockId) -> Option<BlockId> {
+        self.idom[block.0]
+    }
+
+    /// The blocks `block` is the immediate dominator of, in the order of the
+    /// blocks.
+    pub fn children(&self, block: BlockId) -> &[BlockId] {
+        &self.children[block.0]
+    }
+
+    /// Whether every path from the entry to `b` goes through `a`, which is
+    /// also the case when they are the same block.
+    pub fn dominates(&self, a: BlockId, b: BlockId) -> bool {
+        self.is_reachable(b)
+            && self.enter[a.0] <= self.enter[b.0]
+            && self.leave[b.0] <= self.leave[a.0]
+    }
+
+    pub fn is_reachable(&self, block: BlockId) -> bool {
+        self.enter[block.0] != usize::MAX
+    }
+
+    /// The blocks of the tree from the entry down, every block before the
+    /// blocks it dominates.
+    pub fn preorder(&self) -> Vec<BlockId> {
+        let mut order = vec![];
+        let mut stack = vec![Cfg::ENTRY];
+        while let Some(block) = stack.pop() {
+            order.push(block);
+            stack.extend(self.children[block.0].iter().rev());
+        }
+        order
+    }
+
+    /// The dominance frontier of every block: the blocks where a path from
+    /// it meets a path that does not go through it, and where a variable it
+    /// assigns might need a [phi](super::Instr::Phi).
+    pub fn frontiers(&self, cfg: &Cfg) -> Vec<Vec<BlockId>> {
+        let mut frontiers: Vec<Vec<BlockId>> = vec![vec![]; cfg.blocks.len()];
+        for (i, block) in cfg.blocks.iter().enumerate() {
+            if block.preds.len() < 2 || !self.is_reachable(BlockId(i)) {
+                continue;
+            }
+            for &pred in &block.preds {
+                let mut runner = Some(pred);
+                while let Some(at) =
+                    runner.filter(|&at| self.is_reachable(at) && Some(at) != self.idom(BlockId(i)))
+                {
+                    if !frontiers[at.0].contains(&BlockId(i)) {
+                        frontiers[at.0].push(BlockId(i));
+                    }
+                    runner = self.idom(at);
+                }
+            }
+        }
+        frontiers
+    }
+}
+
+/// The blocks that can be reached from the entry, every block before its
+/// successors except along back edges.
+fn reverse_postorder(cfg: &Cfg) -> Vec<BlockId> {
+    let mut visited = vec![false; cfg.blocks.len()];
+    let mut order = vec![];
+    let mut stack = vec![(Cfg::ENTRY, 0)];
+    visited[Cfg::ENTRY.0] = true;
+    while let Some((block, next)) = stack.pop() {
+        match cfg.blocks[block.0].succs.get(next) {
+            Some(&succ) => {
+                stack.push((block, next + 1));
+                if !visited[succ.0] {
+                    visited[succ.0] = true;
+                    stack.push((succ, 0));
+                }
+            }
+            None => order.push(block),
+        }
+    }
+    order.reverse();
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{opt::OptLevel, test_module};
+
+    #[test]
+    fn finds_dominators_and_frontiers() {
+        let module = test_module(
+            "int f(int n) {\n\
+             \x20 int x = 0;\n\
+             \x20 for (int i = 0; i < n; i = i + 1) {\n\
+             \x20   if (i % 2 == 0) { x = x + i; } else { x = x - 1; }\n\
+             \x20 }\n\
+             \x20 return x;\n\
+             }\n\
+             int main() { return f(3); }",
+            OptLevel::O1,
+        );
+        // The entry, then `L0` to `L5`: the loop test, the `if` test, its
+        // two sides, the update and the return.
+        let cfg = Cfg::new(&module.functions[0]);
+        let dominators = Dominators::new(&cfg);
+        let idoms: Vec<Option<usize>> = (0..cfg.blocks.len())
+            .map(|block| dominators.idom(BlockId(block)).map(|idom| idom.0))
+            .collect();
+        assert_eq!(
+            idoms,
+            [None, Some(0), Some(1), Some(2), Some(2), Some(2), Some(1)]
+        );
+        assert_eq!(dominators.preorder(), [0, 1, 2, 3, 4, 5, 6].map(BlockId));
+        assert!(dominators.dominates(BlockId(2), BlockId(4)));
+        assert!(!dominators.dominates(BlockId(3), BlockId(5)));
+
+        // `x` is assigned on both sides of the `if`, which meet at the
+        // update, and in the loop, which meets the entry at the test.
+        let frontiers: Vec<Vec<usize>> = dominators
+            .frontiers(&cfg)
+            .into_iter()
+            .map(|frontier| frontier.into_iter().map(|block| block.0).collect())
+            .collect();
+        assert_eq!(
+            frontiers,
+            [vec![], vec![1], vec![1], vec![5], vec![5], vec![1], vec![]]
+        );
+    }
+}
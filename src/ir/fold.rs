@@ -0,0 +1,308 @@
+//! Constant folding and propagation.
+//!
+//! Finds the variables that hold the same constant on every path that can
+//! reach an instruction, replaces reads of them with the constant and
+//! computes what can be computed at compile time. Branches on a constant
+//! become jumps, and the blocks that are only reached through the branch
+//! not taken are not looked at, so `x = 1; if (false) { x = 2; }` leaves
+//! `x` a constant. Arithmetic that would divide by zero or overflow is left
+//! for when the program runs.
+
+use std::collections::HashSet;
+
+use crate::semantic::consts;
+
+use super::{
+    cfg::{BlockId, Cfg},
+    Function, Instr, Operand, UnOp, VarId,
+};
+
+/// What is known about a variable at a point of the function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Value {
+    /// Not assigned on any path that can reach the point yet.
+    Unassigned,
+    Const(i32),
+    /// Different on different paths, or not known at compile time.
+    Varying,
+}
+
+impl Value {
+    /// What is known where two paths meet.
+    fn meet(self, other: Value) -> Value {
+        match (self, other) {
+            (Value::Unassigned, value) | (value, Value::Unassigned) => value,
+            (Value::Const(a), Value::Const(b)) if a == b => Value::Const(a),
+            _ => Value::Varying,
+        }
+    }
+}
+
+/// What is known about every variable, by [`VarId`].
+type State = Vec<Value>;
+
+/// Folds the constants of `function`, returning whether anything changed.
+pub fn fold(function: &mut Function) -> bool {
+    let mut cfg = Cfg::new(function);
+    let before = cfg.clone();
+    let inputs = propagate(&cfg, function);
+    for (i, block) in cfg.blocks.iter_mut().enumerate() {
+        if let Some(state) = &inputs[i] {
+            let mut state = state.clone();
+            for instr in &mut block.instrs {
+                rewrite(instr, &state);
+                transfer(instr, &mut state);
+            }
+        }
+    }
+    if cfg == before {
+        return false;
+    }
+    cfg.connect();
+    cfg.write_to(function);
+    true
+}
+
+/// What is known at the start of every block, or `None` for the blocks
+/// control cannot reach.
+fn propagate(cfg: &Cfg, function: &Function) -> Vec<Option<State>> {
+    let mut entry = vec![Value::Unassigned; function.vars.len()];
+    for param in &function.params {
+        entry[param.0] = Value::Varying;
+    }
+    let mut inputs: Vec<Option<State>> = vec![None; cfg.blocks.len()];
+    let mut outputs: Vec<Option<State>> = vec![None; cfg.blocks.len()];
+    // The edges control can take, as (from, to).
+    let mut edges: HashSet<(BlockId, BlockId)> = HashSet::new();
+    let mut executable = HashSet::from([Cfg::ENTRY]);
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (i, block) in cfg.blocks.iter().enumerate() {
+            let id = BlockId(i);
+            if !executable.contains(&id) {
+                continue;
+            }
+            let mut state = if id == Cfg::ENTRY {
+                entry.clone()
+            } else {
+                let mut state = vec![Value::Unassigned; function.vars.len()];
+                for pred in block
+                    .preds
+                    .iter()
+                    .filter(|&&pred| edges.contains(&(pred, id)))
+                {
+                    if let Some(output) = &outputs[pred.0] {
+                        for (value, &other) in state.iter_mut().zip(output) {
+                            *value = value.meet(other);
+                        }
+                    }
+                }
+                state
+            };
+            inputs[i] = Some(state.clone());
+            for instr in &block.instrs {
+                transfer(instr, &mut state);
+            }
+            let taken: Vec<BlockId> = match block.instrs.last() {
+                Some(Instr::Branch { cond, .. }) => match operand(*cond, &state) {
+                    Value::Const(0) => vec![block.succs[block.succs.len() - 1]],
+                    Value::Const(_) => vec![block.succs[0]],
+                    Value::Unassigned => vec![],
+                    Value::Varying => block.succs.clone(),
+                },
+                _ => block.succs.clone(),
+            };
+            for succ in taken {
+                changed |= edges.insert((id, succ));
+                changed |= executable.insert(succ);
+            }
+            if outputs[i].as_ref() != Some(&state) {
+                outputs[i] = Some(state);
+                changed = true;
+            }
+        }
+    }
+    inputs
+}
+
+fn operand(operand: Operand, state: &State) -> Value {
+    match operand {
+        Operand::Const(value) => Value::Const(value),
+        Operand::Var(var) => state[var.0],
+    }
+}
+
+/// Applies `f` to the known values of the operands, unless one of them is
+/// not assigned yet or varies.
+fn apply(values: &[Value], f: impl FnOnce(&[i32]) -> Option<i32>) -> Value {
+    if values.contains(&Value::Varying) {
+        return Value::Varying;
+    }
+    if values.contains(&Value::Unassigned) {
+        return Value::Unassigned;
+    }
+    let values: Vec<i32> = values
+        .iter()
+        .map(|value| match value {
+            Value::Const(value) => *value,
+            _ => unreachable!("checked above"),
+        })
+        .collect();
+    f(&values).map_or(Value::Varying, Value::Const)
+}
+
+/// Updates `state` with what `instr` writes.
+fn transfer(instr: &Instr, state: &mut State) {
+    let value = match instr {
+        Instr::Copy { src, .. } => operand(*src, state),
+        Instr::Binary { op, lhs, rhs, .. } => {
+            apply(&[operand(*lhs, state), operand(*rhs, state)], |values| {
+                consts::binary(*op, values[0], values[1])
+            })
+        }
+        Instr::Unary {
+            op: UnOp::Not,
+            operand: x,
+            ..
+        } => apply(&[operand(*x, state)], |values| {
+            Some(i32::from(values[0] == 0))
+        }),
+        _ => Value::Varying,
+    };
+    if let Some(dst) = instr.def() {
+        state[dst.0] = value;
+    }
+}
+
+/// Replaces the reads of constants in `instr` with the constants, and
+/// `instr` with a copy of its result when that is constant.
+fn rewrite(instr: &mut Instr, state: &State) {
+    let constant = |operand: &mut Operand| {
+        if let Operand::Var(var) = operand {
+            if let Value::Const(value) = state[var.0] {
+                *operand = Operand::Const(value);
+            }
+        }
+    };
+    match instr {
+        Instr::Copy { src, .. } => constant(src),
+        Instr::Binary { lhs, rhs, .. } => {
+            constant(lhs);
+            constant(rhs);
+        }
+        Instr::Unary { operand, .. } => constant(operand),
+        Instr::SetGlobal { value, .. } | Instr::Param(value) | Instr::Print { value, .. } => {
+            constant(value)
+        }
+        Instr::Return(value) => value.iter_mut().for_each(constant),
+        Instr::Load { index, .. } => constant(index),
+        Instr::Store { index, value, .. } => {
+            constant(index);
+            constant(value);
+        }
+        Instr::Branch { cond, .. } => constant(cond),
+        Instr::GetGlobal { .. }
+        | Instr::Clear(_)
+        | Instr::Label(_)
+        | Instr::Jump(_)
+        | Instr::Call { .. }
+        | Instr::PrintString(_)
+        | Instr::Phi { .. } => {}
+    }
+    let folded: Option<(VarId, i32)> = match instr {
+        Instr::Binary {
+            dst,
+            op,
+            lhs: Operand::Const(lhs),
+            rhs: Operand::Const(rhs),
+        } => consts::binary(*op, *lhs, *rhs).map(|value| (*dst, value)),
+        Instr::Unary {
+            dst,
+            op: UnOp::Not,
+            operand: Operand::Const(value),
+        } => Some((*dst, i32::from(*value == 0))),
+        _ => None,
+    };
+    if let Some((dst, value)) = folded {
+        *instr = Instr::Copy {
+            dst,
+            src: Operand::Const(value),
+        };
+    }
+    if let Instr::Branch {
+        cond: Operand::Const(value),
+        then,
+        otherwise,
+    } = instr
+    {
+        *instr = Instr::Jump(if *value != 0 { *then } else { *otherwise });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{opt::OptLevel, test_ir, test_module};
+
+    #[test]
+    fn folds_constants_along_the_paths_taken() {
+        let module = test_module(
+            "int f(int p) {\n\
+             \x20 int x = 1;\n\
+             \x20 if (false) { x = 2; }\n\
+             \x20 int y = x * 3;\n\
+             \x20 if (p > 0) { y = 3; } else { y = 1 + 2; }\n\
+             \x20 int z;\n\
+             \x20 z = 0;\n\
+             \x20 print(y + x, p / z, 2147483647 + y - 1);\n\
+             \x20 for (; y < 10; y = y + 1) { }\n\
+             \x20 return y;\n\
+             }\n\
+             int main() { return f(1); }",
+            OptLevel::O0,
+        );
+        let mut f = module.functions[0].clone();
+        assert!(fold(&mut f));
+        let ir = test_ir(&f, &module);
+        // `y` varies in the loop, and neither the division by zero nor the
+        // overflow is folded.
+        assert_eq!(
+            ir,
+            "function f(p) {\n\
+             \x20   x = 1\n\
+             \x20   goto L1\n\
+             L0:\n\
+             \x20   x = 2\n\
+             L1:\n\
+             \x20   y = 3\n\
+             \x20   t0 = p > 0\n\
+             \x20   if t0 goto L2 else goto L3\n\
+             L2:\n\
+             \x20   y = 3\n\
+             \x20   goto L4\n\
+             L3:\n\
+             \x20   y = 3\n\
+             L4:\n\
+             \x20   z = 0\n\
+             \x20   t1 = 4\n\
+             \x20   print int 4\n\
+             \x20   t2 = p / 0\n\
+             \x20   print int t2\n\
+             \x20   t3 = 2147483647 + 3\n\
+             \x20   t4 = t3 - 1\n\
+             \x20   print int t4\n\
+             L5:\n\
+             \x20   t5 = y < 10\n\
+             \x20   if t5 goto L6 else goto L8\n\
+             L6:\n\
+             L7:\n\
+             \x20   y = y + 1\n\
+             \x20   goto L5\n\
+             L8:\n\
+             \x20   return y\n\
+             }\n"
+        );
+        assert!(!fold(&mut f));
+    }
+}
@@ -0,0 +1,351 @@
+//! Common subexpression elimination on [SSA form](super::ssa).
+//!
+//! [`number_values`] walks the dominator tree remembering what every
+//! instruction computes, and turns an instruction that computes what one
+//! that dominates it already did into a copy of its result, which
+//! [copy propagation](super::copies) then removes. Arithmetic is remembered
+//! across blocks, but loads and reads of globals only within a block, and
+//! until a store, a call or a write to the global changes what they would
+//! read. [`hoist`] moves what both sides of a branch compute first into the
+//! block that branches.
+
+use std::collections::HashMap;
+
+use super::{
+    cfg::{BlockId, Cfg},
+    dce::has_effect,
+    dom::Dominators,
+    Array, BinOp, Function, GlobalId, Instr, Operand, UnOp, VarId,
+};
+
+/// What an instruction computes, with the operands of commutative
+/// operators in order and `>` and `>=` turned around, so that `a + b` and
+/// `b + a`, or `a > b` and `b < a`, are the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Expr {
+    Binary(BinOp, Operand, Operand),
+    Unary(UnOp, Operand),
+    Load(Array, Operand),
+    GetGlobal(GlobalId),
+}
+
+impl Expr {
+    /// What `instr` computes, with `value` of its operands, `None` for the
+    /// instructions that do more than compute a value or are copies.
+    fn of(instr: &Instr, value: impl Fn(Operand) -> Operand) -> Option<Expr> {
+        Some(match *instr {
+            Instr::Binary { op, lhs, rhs, .. } => {
+                let (lhs, rhs) = (value(lhs), value(rhs));
+                match op {
+                    BinOp::Add | BinOp::Mul | BinOp::Eq | BinOp::Ne | BinOp::And | BinOp::Or
+                        if rhs < lhs =>
+                    {
+                        Expr::Binary(op, rhs, lhs)
+                    }
+                    BinOp::Gt => Expr::Binary(BinOp::Lt, rhs, lhs),
+                    BinOp::Ge => Expr::Binary(BinOp::Le, rhs, lhs),
+                    _ => Expr::Binary(op, lhs, rhs),
+                }
+            }
+            Instr::Unary { op, operand, .. } => Expr::Unary(op, value(operand)),
+            Instr::Load { array, index, .. } => Expr::Load(array, value(index)),
+            Instr::GetGlobal { global, .. } => Expr::GetGlobal(global),
+            _ => return None,
+        })
+    }
+
+    fn reads_memory(&self) -> bool {
+        matches!(self, Expr::Load(..) | Expr::GetGlobal(_))
+    }
+
+    fn operands(&self) -> Vec<Operand> {
+        match *self {
+            Expr::Binary(_, lhs, rhs) => vec![lhs, rhs],
+            Expr::Unary(_, operand) | Expr::Load(_, operand) => vec![operand],
+            Expr::GetGlobal(_) => vec![],
+        }
+    }
+}
+
+/// Replaces the instructions of `function` that compute what an earlier
+/// one did with copies, returning whether anything changed.
+pub fn number_values(function: &mut Function) -> bool {
+    let mut cfg = Cfg::new(function);
+    let dominators = Dominators::new(&cfg);
+    let mut numbering = Numbering {
+        values: (0..function.vars.len())
+            .map(|var| Operand::Var(VarId(var)))
+            .collect(),
+        available: HashMap::new(),
+        changed: false,
+    };
+    numbering.number(Cfg::ENTRY, &mut cfg, &dominators);
+    if numbering.changed {
+        cfg.write_to(function);
+    }
+    numbering.changed
+}
+
+struct Numbering {
+    /// What every variable is known to be equal to, itself when nothing
+    /// better is known.
+    values: Vec<Operand>,
+    /// The variable that holds what each expression computes, on the path
+    /// from the entry to the current block.
+    available: HashMap<Expr, VarId>,
+    changed: bool,
+}
+
+impl Numbering {
+    /// Numbers the values of `block` and of the blocks it dominates.
+    fn number(&mut self, block: BlockId, cfg: &mut Cfg, dominators: &Dominators) {
+        let mut added = vec![];
+        // What the loads and reads of globals of the block computed, until
+        // memory changes.
+        let mut memory: HashMap<Expr, VarId> = HashMap::new();
+        for instr in &mut cfg.blocks[block.0].instrs {
+            match *instr {
+                Instr::Store { array, .. } => {
+                    memory.retain(|expr, _| !matches!(expr, Expr::Load(a, _) if *a == array))
+                }
+                Instr::Clear(array) => memory.retain(
+                    |expr, _| !matches!(expr, Expr::Load(a, _) if *a == Array::Local(array)),
+                ),
+                Instr::SetGlobal { global, .. } => {
+                    memory.remove(&Expr::GetGlobal(global));
+                }
+                // Only globals are shared with other functions.
+                Instr::Call { .. } => memory.retain(|expr, _| {
+                    !matches!(expr, Expr::Load(Array::Global(_), _) | Expr::GetGlobal(_))
+                }),
+                _ => {}
+            }
+            let Some(dst) = instr.def() else {
+                continue;
+            };
+            if let Instr::Copy { src, .. } = *instr {
+                self.values[dst.0] = self.value(src);
+                continue;
+            }
+            let Some(expr) = Expr::of(instr, |operand| self.value(operand)) else {
+                continue;
+            };
+            let table = if expr.reads_memory() {
+                &mut memory
+            } else {
+                &mut self.available
+            };
+            match table.get(&expr) {
+                Some(&var) => {
+                    *instr = Instr::Copy {
+                        dst,
+                        src: Operand::Var(var),
+                    };
+                    self.values[dst.0] = self.value(Operand::Var(var));
+                    self.changed = true;
+                }
+                None => {
+                    table.insert(expr, dst);
+                    if !expr.reads_memory() {
+                        added.push(expr);
+                    }
+                }
+            }
+        }
+        for &child in dominators.children(block) {
+            self.number(child, cfg, dominators);
+        }
+        for expr in added {
+            self.available.remove(&expr);
+        }
+    }
+
+    fn value(&self, operand: Operand) -> Operand {
+        match operand {
+            Operand::Var(var) => self.values[var.0],
+            Operand::Const(_) => operand,
+        }
+    }
+}
+
+/// Moves an instruction that both sides of a branch start by computing,
+/// after instructions that do nothing but compute, into the block that
+/// branches, returning whether anything changed. The side it is not moved
+/// from copies its result.
+pub fn hoist(function: &mut Function) -> bool {
+    let mut cfg = Cfg::new(function);
+    // The block that writes every variable.
+    let mut defs: Vec<Option<BlockId>> = vec![None; function.vars.len()];
+    for (i, block) in cfg.blocks.iter().enumerate() {
+        for dst in block.instrs.iter().filter_map(Instr::def) {
+            defs[dst.0] = Some(BlockId(i));
+        }
+    }
+    let mut changed = false;
+    for i in 0..cfg.blocks.len() {
+        let &[a, b] = cfg.blocks[i].succs.as_slice() else {
+            continue;
+        };
+        if a == b || cfg.blocks[a.0].preds.len() != 1 || cfg.blocks[b.0].preds.len() != 1 {
+            continue;
+        }
+        loop {
+            let movable = |expr: &Expr| {
+                expr.operands().iter().all(|operand| match operand {
+                    Operand::Var(var) => defs[var.0] != Some(a) && defs[var.0] != Some(b),
+                    Operand::Const(_) => true,
+                })
+            };
+            let Some((from, to)) =
+                common(&cfg.blocks[a.0].instrs, &cfg.blocks[b.0].instrs, movable)
+            else {
+                break;
+            };
+            let instr = cfg.blocks[a.0].instrs.remove(from);
+            let dst = instr.def().expect("computes a value");
+            let other = &mut cfg.blocks[b.0].instrs[to];
+            *other = Instr::Copy {
+                dst: other.def().expect("computes a value"),
+                src: Operand::Var(dst),
+            };
+            let instrs = &mut cfg.blocks[i].instrs;
+            let end = instrs.len() - 1;
+            instrs.insert(end, instr);
+            defs[dst.0] = Some(BlockId(i));
+            changed = true;
+        }
+    }
+    if changed {
+        cfg.write_to(function);
+    }
+    changed
+}
+
+/// The positions in `a` and in `b` of instructions that compute the same
+/// expression from `movable` operands, and only come after instructions
+/// that do nothing but compute.
+fn common(a: &[Instr], b: &[Instr], movable: impl Fn(&Expr) -> bool) -> Option<(usize, usize)> {
+    let candidates = |instrs: &[Instr]| -> Vec<(usize, Expr)> {
+        let quiet = instrs.iter().take_while(|instr| !has_effect(instr)).count();
+        instrs
+            .iter()
+            .enumerate()
+            .take(quiet + 1)
+            .filter_map(|(i, instr)| Some((i, Expr::of(instr, |operand| operand)?)))
+            .filter(|(_, expr)| movable(expr))
+            .collect()
+    };
+    let b = candidates(b);
+    candidates(a).into_iter().find_map(|(i, expr)| {
+        b.iter()
+            .find(|(_, other)| *other == expr)
+            .map(|&(j, _)| (i, j))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{opt::OptLevel, ssa::into_ssa, test_ir, test_module, verify::verify, Module};
+
+    /// The IR of `source` at `-O1` and its first function in SSA form.
+    fn ssa(source: &str) -> (Module, Function) {
+        let module = test_module(source, OptLevel::O1);
+        let mut function = module.functions[0].clone();
+        into_ssa(&mut function);
+        (module, function)
+    }
+
+    #[test]
+    fn replaces_what_was_computed_already_with_copies() {
+        let (module, mut f) = ssa("int a[4];\n\
+             int f(int x, int y, int i) {\n\
+             \x20 int s = a[i] + a[i];\n\
+             \x20 a[0] = s;\n\
+             \x20 int u = a[i];\n\
+             \x20 int p = x * y;\n\
+             \x20 if (x > y) { p = p + y * x; } else { s = s - u; }\n\
+             \x20 bool less = y < x;\n\
+             \x20 if (less) { print(p); }\n\
+             \x20 return p + s;\n\
+             }\n\
+             int main() { return f(1, 2, 3); }");
+        assert!(number_values(&mut f));
+        assert_eq!(verify(&f, true), Ok(()));
+        // `y * x` is `x * y` and `y < x` is `x > y`, but `a[i]` might have
+        // changed when it is read again.
+        assert_eq!(
+            test_ir(&f, &module),
+            "function f(x, y, i) {\n\
+             \x20   t0 = a[i]\n\
+             \x20   t1 = t0\n\
+             \x20   s = t0 + t1\n\
+             \x20   a[0] = s\n\
+             \x20   u = a[i]\n\
+             \x20   p = x * y\n\
+             \x20   t2 = x > y\n\
+             \x20   if t2 goto L0 else goto L1\n\
+             L0:\n\
+             \x20   t3 = p\n\
+             \x20   p.1 = p + t3\n\
+             \x20   goto L2\n\
+             L1:\n\
+             \x20   s.1 = s - u\n\
+             L2:\n\
+             \x20   s.2 = phi [s, L0], [s.1, L1]\n\
+             \x20   p.2 = phi [p.1, L0], [p, L1]\n\
+             \x20   less = t2\n\
+             \x20   if less goto L3 else goto L4\n\
+             L3:\n\
+             \x20   print int p.2\n\
+             L4:\n\
+             \x20   t4 = p.2 + s.2\n\
+             \x20   return t4\n\
+             }\n"
+        );
+        assert!(!number_values(&mut f));
+    }
+
+    #[test]
+    fn hoists_what_both_sides_of_a_branch_compute() {
+        let (module, mut f) = ssa("int f(int x, int y) {\n\
+             \x20 int r;\n\
+             \x20 if (x > 0) { r = x / y + 1; print(r); } else { r = x / y - 1; }\n\
+             \x20 if (r > 0) { print(1); r = r * y; } else { r = r * y; }\n\
+             \x20 return r;\n\
+             }\n\
+             int main() { return f(1, 2); }");
+        assert!(hoist(&mut f));
+        assert_eq!(verify(&f, true), Ok(()));
+        // `r * y` stays on both sides, after what is printed.
+        assert_eq!(
+            test_ir(&f, &module),
+            "function f(x, y) {\n\
+             \x20   t0 = x > 0\n\
+             \x20   t1 = x / y\n\
+             \x20   if t0 goto L0 else goto L1\n\
+             L0:\n\
+             \x20   r = t1 + 1\n\
+             \x20   print int r\n\
+             \x20   goto L2\n\
+             L1:\n\
+             \x20   t2 = t1\n\
+             \x20   r.1 = t2 - 1\n\
+             L2:\n\
+             \x20   r.2 = phi [r, L0], [r.1, L1]\n\
+             \x20   t3 = r.2 > 0\n\
+             \x20   if t3 goto L3 else goto L4\n\
+             L3:\n\
+             \x20   print int 1\n\
+             \x20   r.3 = r.2 * y\n\
+             \x20   goto L5\n\
+             L4:\n\
+             \x20   r.4 = r.2 * y\n\
+             L5:\n\
+             \x20   r.5 = phi [r.3, L3], [r.4, L4]\n\
+             \x20   return r.5\n\
+             }\n"
+        );
+        assert!(!hoist(&mut f));
+    }
+}
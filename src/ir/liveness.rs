@@ -0,0 +1,103 @@
+//! Which variables are live, that is might still be read, at the edges of
+//! every block.
+//!
+//! The argument of a [phi](Instr::Phi) is only read when control comes
+//! from the block it names, so it is live at the end of that block and not
+//! at the start of the block of the phi, where the phi writes its result.
+
+use super::{
+    cfg::{BlockId, Cfg},
+    Instr, Operand,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Liveness {
+    /// The variables live at the start of every block, by
+    /// [`VarId`](super::VarId).
+    pub live_in: Vec<Vec<bool>>,
+    /// The variables live at the end of every block.
+    pub live_out: Vec<Vec<bool>>,
+}
+
+impl Liveness {
+    /// Solves the backward data-flow problem for a function with `vars`
+    /// variables.
+    pub fn new(cfg: &Cfg, vars: usize) -> Self {
+        // What each block reads before writing it, and what it writes.
+        let (uses, defs): (Vec<Vec<bool>>, Vec<Vec<bool>>) = cfg
+            .blocks
+            .iter()
+            .map(|block| {
+                let mut uses = vec![false; vars];
+                let mut defs = vec![false; vars];
+                for instr in &block.instrs {
+                    if !matches!(instr, Instr::Phi { .. }) {
+                        for var in read(instr) {
+                            if !defs[var] {
+                                uses[var] = true;
+                            }
+                        }
+                    }
+                    if let Some(dst) = instr.def() {
+                        defs[dst.0] = true;
+                    }
+                }
+                (uses, defs)
+            })
+            .unzip();
+        // What the phis of each block read at the end of each predecessor.
+        let mut phi_uses = vec![vec![false; vars]; cfg.blocks.len()];
+        for block in &cfg.blocks {
+            for instr in &block.instrs {
+                let Instr::Phi { args, .. } = instr else {
+                    continue;
+                };
+                for (label, value) in args {
+                    if let (Operand::Var(var), Some(pred)) = (value, cfg.block_of(*label)) {
+                        phi_uses[pred.0][var.0] = true;
+                    }
+                }
+            }
+        }
+
+        let mut live_in = uses;
+        let mut live_out = phi_uses;
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (i, block) in cfg.blocks.iter().enumerate().rev() {
+                for succ in &block.succs {
+                    for var in 0..vars {
+                        if live_in[succ.0][var] && !live_out[i][var] {
+                            live_out[i][var] = true;
+                            changed = true;
+                        }
+                    }
+                }
+                for var in 0..vars {
+                    if live_out[i][var] && !defs[i][var] && !live_in[i][var] {
+                        live_in[i][var] = true;
+                        changed = true;
+                    }
+                }
+            }
+        }
+        Liveness { live_in, live_out }
+    }
+
+    /// The variables live at the end of `block`.
+    pub fn live_out(&self, block: BlockId) -> &[bool] {
+        &self.live_out[block.0]
+    }
+}
+
+/// The variables `instr` reads, by index.
+pub fn read(instr: &Instr) -> impl Iterator<Item = usize> {
+    instr
+        .uses()
+        .into_iter()
+        .filter_map(|operand| match operand {
+            Operand::Var(var) => Some(var.0),
+            Operand::Const(_) => None,
+        })
+}
@@ -0,0 +1,640 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::{BinaryOp, Block, Else, Expr, FunctionDecl, Item, Literal, Program, Stmt, UnaryOp},
+    semantic::{
+        symbols::{SymbolId, SymbolTable},
+        types::Type,
+        ExprTypes,
+    },
+    token::Span,
+};
+
+use super::{
+    Array, Function, Global, GlobalId, Instr, Label, Module, Operand, PrintKind, UnOp, VarId,
+    VarKind, INIT,
+};
+
+/// Lowers `program` into a [`Module`]. `program` has to have passed the
+/// semantic checks, with `symbols` and `types` the table and types they
+/// built.
+///
+/// Conditions are lowered to jumps, so `&&` and `||` only evaluate their
+/// right-hand side when they need it. `main` starts by calling [`INIT`] when
+/// there is one.
+pub fn lower(program: &Program, symbols: &SymbolTable, types: &ExprTypes) -> Module {
+    let mut module = Module::default();
+    let mut globals = HashMap::new();
+    let mut init = Function::new(INIT);
+    for item in &program.items {
+        let Item::Var(var) = item else {
+            continue;
+        };
+        let id = symbol(symbols, var.name.span);
+        globals.insert(id, GlobalId(module.globals.len()));
+        module.globals.push(Global {
+            name: var.name.name.clone(),
+            len: array_len(&symbols[id].ty),
+        });
+        if let Some(value) = &var.init {
+            let mut lowering = Lowering::new(&mut init, symbols, types, &globals);
+            let value = lowering.expr(value, None);
+            lowering.emit(Instr::SetGlobal {
+                global: globals[&id],
+                value,
+            });
+        }
+    }
+    let has_init = !init.body.is_empty();
+    if has_init {
+        init.body.push(Instr::Return(None));
+        module.functions.push(init);
+    }
+
+    for item in &program.items {
+        let Item::Function(function) = item else {
+            continue;
+        };
+        let mut lowered = Function::new(&function.name.name);
+        let mut lowering = Lowering::new(&mut lowered, symbols, types, &globals);
+        if has_init && function.name.name == "main" {
+            lowering.emit(Instr::Call {
+                dst: None,
+                function: INIT.to_owned(),
+                args: 0,
+            });
+        }
+        lowering.function(function);
+        lowered.number_labels();
+        module.functions.push(lowered);
+    }
+    module
+}
+
+/// The symbol the name at `span` was resolved to, or declares.
+fn symbol(symbols: &SymbolTable, span: Span) -> SymbolId {
+    symbols.reference(span).expect("checked names are resolved")
+}
+
+/// The number of elements of an array of type `ty`, all dimensions
+/// together, or `None` when it is not an array.
+fn array_len(ty: &Type) -> Option<usize> {
+    match ty {
+        Type::Array(element, len) => Some(len.saturating_mul(array_len(element).unwrap_or(1))),
+        _ => None,
+    }
+}
+
+/// The sizes of the dimensions of an array of type `ty`, outermost first.
+fn dims(mut ty: &Type) -> Vec<usize> {
+    let mut dims = vec![];
+    while let Type::Array(element, len) = ty {
+        dims.push(*len);
+        ty = element;
+    }
+    dims
+}
+
+/// Where a variable of the source lives.
+#[derive(Clone, Copy)]
+enum Place {
+    Local(VarId),
+    Global(GlobalId),
+}
+
+/// Lowers the statements and expressions of one function.
+struct Lowering<'a> {
+    function: &'a mut Function,
+    symbols: &'a SymbolTable,
+    types: &'a ExprTypes,
+    globals: &'a HashMap<SymbolId, GlobalId>,
+    locals: HashMap<SymbolId, VarId>,
+    /// Where `continue` and `break` jump to in the loops around the
+    /// statement being lowered, innermost last.
+    loops: Vec<(Label, Label)>,
+}
+
+impl<'a> Lowering<'a> {
+    fn new(
+        function: &'a mut Function,
+        symbols: &'a SymbolTable,
+        types: &'a ExprTypes,
+        globals: &'a HashMap<SymbolId, GlobalId>,
+    ) -> Self {
+        Self {
+            function,
+            symbols,
+            types,
+            globals,
+            locals: HashMap::new(),
+            loops: vec![],
+        }
+    }
+
+    fn emit(&mut self, instr: Instr) {
+        self.function.body.push(instr);
+    }
+
+    /// Jumps to `label`, unless control never gets here.
+    fn jump(&mut self, label: Label) {
+        if !self.function.body.last().is_some_and(Instr::is_terminator) {
+            self.emit(Instr::Jump(label));
+        }
+    }
+
+    fn function(&mut self, function: &FunctionDecl) {
+        for param in &function.params {
+            let id = self.new_local(param.name.span, &param.name.name, VarKind::Param);
+            self.function.params.push(id);
+        }
+        self.block(&function.body);
+    }
+
+    fn new_local(&mut self, span: Span, name: &str, kind: VarKind) -> VarId {
+        let id = self.function.new_var(name, kind);
+        self.locals.insert(symbol(self.symbols, span), id);
+        id
+    }
+
+    fn place(&self, span: Span) -> Place {
+        let id = symbol(self.symbols, span);
+        match self.locals.get(&id) {
+            Some(&local) => Place::Local(local),
+            None => Place::Global(self.globals[&id]),
+        }
+    }
+
+    fn block(&mut self, block: &Block) {
+        for stmt in &block.stmts {
+            self.stmt(stmt);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Var(var) => {
+                let id = symbol(self.symbols, var.name.span);
+                let kind = match array_len(&self.symbols[id].ty) {
+                    Some(len) => VarKind::Array(len),
+                    None => VarKind::Local,
+                };
+                let local = self.function.new_var(&var.name.name, kind);
+                match &var.init {
+                    Some(init) => {
+                        let value = self.expr(init, Some(local));
+                        self.assign(local, value);
+                    }
+                    None if kind != VarKind::Local => self.emit(Instr::Clear(local)),
+                    // Definite assignment makes sure it is assigned before
+                    // it is read.
+                    None => {}
+                }
+                // Only now, the initializer still sees the variables it
+                // shadows.
+                self.locals.insert(id, local);
+            }
+            Stmt::Assign { target, value, .. } => match target {
+                Expr::Var(name) => match self.place(name.span) {
+                    Place::Local(local) => {
+                        let value = self.expr(value, Some(local));
+                        self.assign(local, value);
+                    }
+                    Place::Global(global) => {
+                        let value = self.expr(value, None);
+                        self.emit(Instr::SetGlobal { global, value });
+                    }
+                },
+                _ => {
+                    let value = self.expr(value, None);
+                    let (array, index) = self.element(target);
+                    self.emit(Instr::Store {
+                        array,
+                        index,
+                        value,
+                    });
+                }
+            },
+            Stmt::ExprStmt { expr, .. } => match expr {
+                Expr::Call { callee, args, .. } => {
+                    let args = self.params(args);
+                    self.emit(Instr::Call {
+                        dst: None,
+                        function: callee.name.clone(),
+                        args,
+                    });
+                }
+                _ => {
+                    self.expr(expr, None);
+                }
+            },
+            Stmt::If {
+                cond,
+                then,
+                otherwise,
+                ..
+            } => {
+                let then_label = self.function.new_label();
+                let end = self.function.new_label();
+                let else_label = match otherwise {
+                    Some(_) => self.function.new_label(),
+                    None => end,
+                };
+                self.cond(cond, then_label, else_label);
+                self.emit(Instr::Label(then_label));
+                self.block(then);
+                if let Some(otherwise) = otherwise {
+                    self.jump(end);
+                    self.emit(Instr::Label(else_label));
+                    match otherwise {
+                        Else::If(stmt) => self.stmt(stmt),
+                        Else::Block(block) => self.block(block),
+                    }
+                }
+                self.emit(Instr::Label(end));
+            }
+            Stmt::For {
+                init,
+                cond,
+                update,
+                body,
+                ..
+            } => {
+                for stmt in init {
+                    self.stmt(stmt);
+                }
+                let start = self.function.new_label();
+                let step = self.function.new_label();
+                let end = self.function.new_label();
+                self.emit(Instr::Label(start));
+                if let Some(cond) = cond {
+                    let body_label = self.function.new_label();
+                    self.cond(cond, body_label, end);
+                    self.emit(Instr::Label(body_label));
+                }
+                self.loops.push((step, end));
+                self.block(body);
+                self.loops.pop();
+                self.emit(Instr::Label(step));
+                if let Some(update) = update {
+                    self.stmt(update);
+                }
+                self.jump(start);
+                self.emit(Instr::Label(end));
+            }
+            Stmt::Return { value, .. } => {
+                let value = self.expr(value, None);
+                self.emit(Instr::Return(Some(value)));
+            }
+            Stmt::Print { args, .. } => {
+                for arg in args {
+                    if let Expr::Literal(Literal::String(text), _) = arg {
+                        self.emit(Instr::PrintString(text.clone()));
+                        continue;
+                    }
+                    let kind = match self.types.get(&arg.span()) {
+                        Some(Type::Bool) => PrintKind::Bool,
+                        Some(Type::Char) => PrintKind::Char,
+                        _ => PrintKind::Int,
+                    };
+                    let value = self.expr(arg, None);
+                    self.emit(Instr::Print { kind, value });
+                }
+            }
+            Stmt::Break(_) | Stmt::Continue(_) => {
+                let &(step, end) = self
+                    .loops
+                    .last()
+                    .expect("checked programs only leave loops they are in");
+                self.jump(if matches!(stmt, Stmt::Break(_)) {
+                    end
+                } else {
+                    step
+                });
+            }
+        }
+    }
+
+    /// Stores `value` in the local `dst`, unless it is there already.
+    fn assign(&mut self, dst: VarId, value: Operand) {
+        if value != Operand::Var(dst) {
+            self.emit(Instr::Copy { dst, src: value });
+        }
+    }
+
+    /// Evaluates `expr`, into `dst` when it computes something new. `dst`
+    /// is only written once everything `expr` reads has been read.
+    fn expr(&mut self, expr: &Expr, dst: Option<VarId>) -> Operand {
+        match expr {
+            Expr::Literal(literal, _) => Operand::Const(match literal {
+                Literal::Int(n) => *n,
+                Literal::Bool(b) => i32::from(*b),
+                Literal::Char(c) => *c as i32,
+                Literal::String(_) => panic!("strings can only be printed"),
+            }),
+            Expr::Var(name) => match self.place(name.span) {
+                Place::Local(local) => Operand::Var(local),
+                Place::Global(global) => {
+                    let dst = self.dst(dst);
+                    self.emit(Instr::GetGlobal { dst, global });
+                    Operand::Var(dst)
+                }
+            },
+            Expr::Index { .. } => {
+                let (array, index) = self.element(expr);
+                let dst = self.dst(dst);
+                self.emit(Instr::Load { dst, array, index });
+                Operand::Var(dst)
+            }
+            Expr::Call { callee, args, .. } => {
+                let args = self.params(args);
+                let dst = self.dst(dst);
+                self.emit(Instr::Call {
+                    dst: Some(dst),
+                    function: callee.name.clone(),
+                    args,
+                });
+                Operand::Var(dst)
+            }
+            Expr::Unary {
+                op: UnaryOp::Not,
+                operand,
+                ..
+            } => {
+                let operand = self.expr(operand, None);
+                let dst = self.dst(dst);
+                self.emit(Instr::Unary {
+                    dst,
+                    op: UnOp::Not,
+                    operand,
+                });
+                Operand::Var(dst)
+            }
+            Expr::Binary {
+                op: BinaryOp::And | BinaryOp::Or,
+                ..
+            } => {
+                let dst = self.dst(dst);
+                let (is_true, is_false, end) = (
+                    self.function.new_label(),
+                    self.function.new_label(),
+                    self.function.new_label(),
+                );
+                self.cond(expr, is_true, is_false);
+                self.emit(Instr::Label(is_true));
+                self.emit(Instr::Copy {
+                    dst,
+                    src: Operand::Const(1),
+                });
+                self.emit(Instr::Jump(end));
+                self.emit(Instr::Label(is_false));
+                self.emit(Instr::Copy {
+                    dst,
+                    src: Operand::Const(0),
+                });
+                self.emit(Instr::Label(end));
+                Operand::Var(dst)
+            }
+            Expr::Binary { op, lhs, rhs, .. } => {
+                let lhs = self.expr(lhs, None);
+                let rhs = self.expr(rhs, None);
+                let dst = self.dst(dst);
+                self.emit(Instr::Binary {
+                    dst,
+                    op: *op,
+                    lhs,
+                    rhs,
+                });
+                Operand::Var(dst)
+            }
+            Expr::Error(_) => panic!("checked programs have no errors"),
+        }
+    }
+
+    /// `dst`, or a new temporary.
+    fn dst(&mut self, dst: Option<VarId>) -> VarId {
+        dst.unwrap_or_else(|| self.function.new_temp())
+    }
+
+    /// Jumps to `then` when `expr` is true and to `otherwise` when it is
+    /// false.
+    fn cond(&mut self, expr: &Expr, then: Label, otherwise: Label) {
+        match expr {
+            Expr::Literal(Literal::Bool(b), _) => {
+                self.emit(Instr::Jump(if *b { then } else { otherwise }))
+            }
+            Expr::Unary {
+                op: UnaryOp::Not,
+                operand,
+                ..
+            } => self.cond(operand, otherwise, then),
+            Expr::Binary {
+                op: BinaryOp::And,
+                lhs,
+                rhs,
+                ..
+            } => {
+                let rest = self.function.new_label();
+                self.cond(lhs, rest, otherwise);
+                self.emit(Instr::Label(rest));
+                self.cond(rhs, then, otherwise);
+            }
+            Expr::Binary {
+                op: BinaryOp::Or,
+                lhs,
+                rhs,
+                ..
+            } => {
+                let rest = self.function.new_label();
+                self.cond(lhs, then, rest);
+                self.emit(Instr::Label(rest));
+                self.cond(rhs, then, otherwise);
+            }
+            _ => {
+                let cond = self.expr(expr, None);
+                self.emit(Instr::Branch {
+                    cond,
+                    then,
+                    otherwise,
+                });
+            }
+        }
+    }
+
+    /// Evaluates `args` and passes them to the call that follows, returning
+    /// how many there are.
+    fn params(&mut self, args: &[Expr]) -> usize {
+        let args: Vec<Operand> = args.iter().map(|arg| self.expr(arg, None)).collect();
+        let count = args.len();
+        for arg in args {
+            self.emit(Instr::Param(arg));
+        }
+        count
+    }
+
+    /// The array and the flattened index of the element `expr` names.
+    fn element(&mut self, expr: &Expr) -> (Array, Operand) {
+        let mut indices = vec![];
+        let mut base = expr;
+        while let Expr::Index { array, index, .. } = base {
+            indices.push(index);
+            base = array;
+        }
+        let Expr::Var(name) = base else {
+            panic!("only variables can be indexed");
+        };
+        let dims = dims(&self.symbols[symbol(self.symbols, name.span)].ty);
+        let mut offset = None;
+        for (index, len) in indices.into_iter().rev().zip(dims) {
+            let index = self.expr(index, None);
+            offset = Some(match offset {
+                None => index,
+                Some(offset) => {
+                    let scaled = self.function.new_temp();
+                    self.emit(Instr::Binary {
+                        dst: scaled,
+                        op: BinaryOp::Mul,
+                        lhs: offset,
+                        rhs: Operand::Const(len as i32),
+                    });
+                    let sum = self.function.new_temp();
+                    self.emit(Instr::Binary {
+                        dst: sum,
+                        op: BinaryOp::Add,
+                        lhs: Operand::Var(scaled),
+                        rhs: index,
+                    });
+                    Operand::Var(sum)
+                }
+            });
+        }
+        let array = match self.place(name.span) {
+            Place::Local(local) => Array::Local(local),
+            Place::Global(global) => Array::Global(global),
+        };
+        (array, offset.expect("checked arrays are indexed"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ir::{opt::OptLevel, test_module};
+
+    /// The IR of `source` as it is lowered, before any constant is folded.
+    fn ir(source: &str) -> String {
+        test_module(source, OptLevel::O0).to_string()
+    }
+
+    #[test]
+    fn lowers_loops_chains_and_short_circuits_to_jumps() {
+        assert_eq!(
+            ir("int f(int n) {\n\
+                \x20 int s = 0;\n\
+                \x20 for (int i = 0; i < n; i = i + 1) {\n\
+                \x20   if (i == 2) { continue; } else if (i > 5 || !(s < 10)) { break; } else { s = s * 2 + i; }\n\
+                \x20 }\n\
+                \x20 bool b = s > 0 && n != 1;\n\
+                \x20 print(b, ':', s);\n\
+                \x20 return s;\n\
+                }\n\
+                int main() { f(3); return 0; }"),
+            "\
+function f(n) {
+    s = 0
+    i = 0
+L0:
+    t0 = i < n
+    if t0 goto L1 else goto L10
+L1:
+    t1 = i == 2
+    if t1 goto L2 else goto L3
+L2:
+    goto L9
+L3:
+    t2 = i > 5
+    if t2 goto L5 else goto L4
+L4:
+    t3 = s < 10
+    if t3 goto L6 else goto L5
+L5:
+    goto L10
+L6:
+    t4 = s * 2
+    s = t4 + i
+L7:
+L8:
+L9:
+    i = i + 1
+    goto L0
+L10:
+    t5 = s > 0
+    if t5 goto L11 else goto L13
+L11:
+    t6 = n != 1
+    if t6 goto L12 else goto L13
+L12:
+    b = 1
+    goto L14
+L13:
+    b = 0
+L14:
+    print bool b
+    print char 58
+    print int s
+    return s
+}
+
+function main() {
+    param 3
+    call f, 1
+    return 0
+}
+"
+        );
+    }
+
+    #[test]
+    fn lowers_globals_arrays_and_shadowed_locals() {
+        assert_eq!(
+            ir("int g = 4;\n\
+                char m[2][3];\n\
+                int main() {\n\
+                \x20 int x = g;\n\
+                \x20 bool a[2];\n\
+                \x20 for (;;) { int x = x + 1; m[1][x] = 'c'; a[0] = m[0][2] == 'c'; break; }\n\
+                \x20 g = x;\n\
+                \x20 return x;\n\
+                }"),
+            "\
+global g
+global m[6]
+
+function $init() {
+    g = 4
+    return
+}
+
+function main() {
+    call $init, 0
+    x = g
+    clear a
+L0:
+    x.1 = x + 1
+    t0 = 1 * 3
+    t1 = t0 + x.1
+    m[t1] = 99
+    t2 = 0 * 3
+    t3 = t2 + 2
+    t4 = m[t3]
+    t5 = t4 == 99
+    a[0] = t5
+    goto L2
+L1:
+    goto L0
+L2:
+    g = x
+    return x
+}
+"
+        );
+    }
+}
@@ -0,0 +1,544 @@
+//! A three-address intermediate representation, lowered from a checked
+//! [`Program`](crate::ast::Program) by [`lower`].
+//!
+//! Every function is a flat list of [`Instr`]s that read at most two
+//! operands and write at most one variable, with [`Label`]s and jumps for
+//! control flow. Values are plain `int`s: `false` and `true` are `0` and `1`
+//! and a `char` is its code point. Scalar globals and arrays are memory,
+//! read and written only through loads and stores, and multi-dimensional
+//! arrays are flattened in row-major order. Indices are not checked.
+//!
+//! The text form, which is what `Display` prints, looks like this:
+//!
+//! ```text
+//! global g
+//! global h[4]
+//!
+//! function f(a, b) {
+//!     t0 = a < b
+//!     if t0 goto L0 else goto L1
+//! L0:
+//!     t1 = g
+//!     h[a] = t1
+//!     param a
+//!     t2 = call f, 1
+//!     return t2
+//! L1:
+//!     print int b
+//!     print "\n"
+//!     return 0
+//! }
+//! ```
+
+use std::{collections::HashMap, fmt};
+
+use crate::ast::BinaryOp;
+
+pub mod cfg;
+pub mod copies;
+pub mod dce;
+pub mod dom;
+pub mod fold;
+pub mod gvn;
+pub mod liveness;
+mod lower;
+pub mod opt;
+pub mod simplify;
+pub mod ssa;
+pub mod verify;
+
+pub use lower::lower;
+
+/// The name of the function that initializes the globals that have an
+/// initializer, in order. It runs before `main`, and only exists when there
+/// is such a global.
+pub const INIT: &str = "$init";
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Module {
+    pub globals: Vec<Global>,
+    pub functions: Vec<Function>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GlobalId(pub usize);
+
+/// A global variable, zero before [`INIT`] runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Global {
+    pub name: String,
+    /// The number of elements of an array, `None` for a scalar.
+    pub len: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct VarId(pub usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Label(pub usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VarKind {
+    Param,
+    Local,
+    /// A local array with this many elements.
+    Array(usize),
+    /// A value computed while evaluating an expression.
+    Temp,
+}
+
+/// A variable of a function. Locals and parameters keep their source
+/// names, with a `.1`, `.2`, ... suffix when a function has several
+/// variables of the same name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Var {
+    pub name: String,
+    pub kind: VarKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub params: Vec<VarId>,
+    pub vars: Vec<Var>,
+    pub body: Vec<Instr>,
+    /// How many labels the body uses, they are numbered from 0.
+    pub labels: usize,
+}
+
+impl Function {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            params: vec![],
+            vars: vec![],
+            body: vec![],
+            labels: 0,
+        }
+    }
+
+    /// A new variable called `name`, or `name.1`, `name.2`, ... when the
+    /// function has one of that name already.
+    pub fn new_var(&mut self, name: impl Into<String>, kind: VarKind) -> VarId {
+        let mut name = name.into();
+        let base = name.clone();
+        let mut suffix = 0;
+        while self.vars.iter().any(|var| var.name == name) {
+            suffix += 1;
+            name = format!("{base}.{suffix}");
+        }
+        self.vars.push(Var { name, kind });
+        VarId(self.vars.len() - 1)
+    }
+
+    /// A new temporary, named `t0`, `t1`, ... in the order they are made.
+    pub fn new_temp(&mut self) -> VarId {
+        let temps = self
+            .vars
+            .iter()
+            .filter(|var| var.kind == VarKind::Temp)
+            .count();
+        self.new_var(format!("t{temps}"), VarKind::Temp)
+    }
+
+    pub fn new_label(&mut self) -> Label {
+        self.labels += 1;
+        Label(self.labels - 1)
+    }
+
+    /// Numbers the labels of the body in the order they appear, from 0.
+    pub fn number_labels(&mut self) {
+        let mut numbers = HashMap::new();
+        for instr in &self.body {
+            if let Instr::Label(label) = instr {
+                numbers.insert(*label, Label(numbers.len()));
+            }
+        }
+        for instr in &mut self.body {
+            match instr {
+                Instr::Label(label) | Instr::Jump(label) => *label = numbers[label],
+                Instr::Branch {
+                    then, otherwise, ..
+                } => {
+                    *then = numbers[then];
+                    *otherwise = numbers[otherwise];
+                }
+                Instr::Phi { args, .. } => {
+                    for (label, _) in args {
+                        *label = numbers[label];
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.labels = numbers.len();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Operand {
+    Const(i32),
+    Var(VarId),
+}
+
+/// An array, local or global.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Array {
+    Local(VarId),
+    Global(GlobalId),
+}
+
+/// The operators of [`Instr::Binary`]: the binary operators of the source
+/// except `&&` and `||`, which are lowered to jumps.
+pub type BinOp = BinaryOp;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnOp {
+    /// `!`, `1` for `0` and `0` otherwise.
+    Not,
+}
+
+/// How [`Instr::Print`] formats a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrintKind {
+    Int,
+    Bool,
+    Char,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Instr {
+    /// `dst = src`
+    Copy { dst: VarId, src: Operand },
+    /// `dst = lhs op rhs`
+    Binary {
+        dst: VarId,
+        op: BinOp,
+        lhs: Operand,
+        rhs: Operand,
+    },
+    /// `dst = op operand`
+    Unary {
+        dst: VarId,
+        op: UnOp,
+        operand: Operand,
+    },
+    /// `dst = g`
+    GetGlobal { dst: VarId, global: GlobalId },
+    /// `g = value`
+    SetGlobal { global: GlobalId, value: Operand },
+    /// `dst = a[index]`
+    Load {
+        dst: VarId,
+        array: Array,
+        index: Operand,
+    },
+    /// `clear a`, sets every element of the local array `a` to `0`.
+    Clear(VarId),
+    /// `a[index] = value`
+    Store {
+        array: Array,
+        index: Operand,
+        value: Operand,
+    },
+    /// `L0:`
+    Label(Label),
+    /// `goto L0`
+    Jump(Label),
+    /// `if cond goto L0 else goto L1`, taking `then` unless `cond` is `0`.
+    Branch {
+        cond: Operand,
+        then: Label,
+        otherwise: Label,
+    },
+    /// `param value`, the next argument of the following [`Instr::Call`].
+    Param(Operand),
+    /// `dst = call f, args`, with the last `args` [`Instr::Param`]s as
+    /// arguments.
+    Call {
+        dst: Option<VarId>,
+        function: String,
+        args: usize,
+    },
+    /// `return value`, without a value only in [`INIT`].
+    Return(Option<Operand>),
+    /// `print int value`
+    Print { kind: PrintKind, value: Operand },
+    /// `print "text"`
+    PrintString(String),
+    /// `dst = phi [a, L0], [b, L1]`, `a` when control came from the block
+    /// labelled `L0` and `b` when it came from `L1`. Only in [SSA
+    /// form](ssa), at the top of a block, with an argument for each of its
+    /// predecessors.
+    Phi {
+        dst: VarId,
+        args: Vec<(Label, Operand)>,
+    },
+}
+
+impl Instr {
+    /// The variable the instruction writes.
+    pub fn def(&self) -> Option<VarId> {
+        match self {
+            Instr::Copy { dst, .. }
+            | Instr::Binary { dst, .. }
+            | Instr::Unary { dst, .. }
+            | Instr::GetGlobal { dst, .. }
+            | Instr::Load { dst, .. }
+            | Instr::Phi { dst, .. } => Some(*dst),
+            Instr::Call { dst, .. } => *dst,
+            _ => None,
+        }
+    }
+
+    /// The operands the instruction reads, in order.
+    pub fn uses(&self) -> Vec<Operand> {
+        match self {
+            Instr::Copy { src, .. } => vec![*src],
+            Instr::Binary { lhs, rhs, .. } => vec![*lhs, *rhs],
+            Instr::Unary { operand, .. } => vec![*operand],
+            Instr::SetGlobal { value, .. } => vec![*value],
+            Instr::Load { index, .. } => vec![*index],
+            Instr::Store { index, value, .. } => vec![*index, *value],
+            Instr::Branch { cond, .. } => vec![*cond],
+            Instr::Param(value) | Instr::Print { value, .. } => vec![*value],
+            Instr::Return(value) => value.iter().copied().collect(),
+            Instr::Phi { args, .. } => args.iter().map(|(_, value)| *value).collect(),
+            Instr::GetGlobal { .. }
+            | Instr::Clear(_)
+            | Instr::Label(_)
+            | Instr::Jump(_)
+            | Instr::Call { .. }
+            | Instr::PrintString(_) => vec![],
+        }
+    }
+
+    /// Like [`Instr::def`], to change the variable.
+    pub fn def_mut(&mut self) -> Option<&mut VarId> {
+        match self {
+            Instr::Copy { dst, .. }
+            | Instr::Binary { dst, .. }
+            | Instr::Unary { dst, .. }
+            | Instr::GetGlobal { dst, .. }
+            | Instr::Load { dst, .. }
+            | Instr::Phi { dst, .. } => Some(dst),
+            Instr::Call { dst, .. } => dst.as_mut(),
+            _ => None,
+        }
+    }
+
+    /// Like [`Instr::uses`], to change the operands.
+    pub fn uses_mut(&mut self) -> Vec<&mut Operand> {
+        match self {
+            Instr::Copy { src, .. } => vec![src],
+            Instr::Binary { lhs, rhs, .. } => vec![lhs, rhs],
+            Instr::Unary { operand, .. } => vec![operand],
+            Instr::SetGlobal { value, .. } => vec![value],
+            Instr::Load { index, .. } => vec![index],
+            Instr::Store { index, value, .. } => vec![index, value],
+            Instr::Branch { cond, .. } => vec![cond],
+            Instr::Param(value) | Instr::Print { value, .. } => vec![value],
+            Instr::Return(value) => value.iter_mut().collect(),
+            Instr::Phi { args, .. } => args.iter_mut().map(|(_, value)| value).collect(),
+            Instr::GetGlobal { .. }
+            | Instr::Clear(_)
+            | Instr::Label(_)
+            | Instr::Jump(_)
+            | Instr::Call { .. }
+            | Instr::PrintString(_) => vec![],
+        }
+    }
+
+    /// The labels a jump or a branch goes to.
+    pub fn targets(&self) -> Vec<Label> {
+        match self {
+            Instr::Jump(label) => vec![*label],
+            Instr::Branch {
+                then, otherwise, ..
+            } => vec![*then, *otherwise],
+            _ => vec![],
+        }
+    }
+
+    /// Whether control never goes on to the next instruction.
+    pub fn is_terminator(&self) -> bool {
+        matches!(
+            self,
+            Instr::Jump(_) | Instr::Branch { .. } | Instr::Return(_)
+        )
+    }
+}
+
+impl fmt::Display for Label {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "L{}", self.0)
+    }
+}
+
+impl fmt::Display for UnOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            UnOp::Not => "!",
+        })
+    }
+}
+
+impl fmt::Display for PrintKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PrintKind::Int => "int",
+            PrintKind::Bool => "bool",
+            PrintKind::Char => "char",
+        })
+    }
+}
+
+impl fmt::Display for Module {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for global in &self.globals {
+            match global.len {
+                Some(len) => writeln!(f, "global {}[{len}]", global.name)?,
+                None => writeln!(f, "global {}", global.name)?,
+            }
+        }
+        for (i, function) in self.functions.iter().enumerate() {
+            if i > 0 || !self.globals.is_empty() {
+                writeln!(f)?;
+            }
+            function.write(f, self)?;
+        }
+        Ok(())
+    }
+}
+
+impl Function {
+    /// Writes the function in the text form, with the names of the globals
+    /// of `module`.
+    pub fn write(&self, f: &mut impl fmt::Write, module: &Module) -> fmt::Result {
+        let params: Vec<&str> = self
+            .params
+            .iter()
+            .map(|&param| self.vars[param.0].name.as_str())
+            .collect();
+        writeln!(f, "function {}({}) {{", self.name, params.join(", "))?;
+        for instr in &self.body {
+            match instr {
+                Instr::Label(label) => writeln!(f, "{label}:")?,
+                instr => writeln!(f, "    {}", self.display(instr, module))?,
+            }
+        }
+        writeln!(f, "}}")
+    }
+
+    /// `instr` in the text form.
+    pub fn display<'a>(&'a self, instr: &'a Instr, module: &'a Module) -> impl fmt::Display + 'a {
+        DisplayInstr {
+            instr,
+            function: self,
+            module,
+        }
+    }
+}
+
+struct DisplayInstr<'a> {
+    instr: &'a Instr,
+    function: &'a Function,
+    module: &'a Module,
+}
+
+impl fmt::Display for DisplayInstr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let var = |id: &VarId| self.function.vars[id.0].name.as_str();
+        let operand = |operand: &Operand| match operand {
+            Operand::Const(value) => value.to_string(),
+            Operand::Var(id) => var(id).to_owned(),
+        };
+        let global = |id: &GlobalId| self.module.globals[id.0].name.as_str();
+        let array = |array: &Array| match array {
+            Array::Local(id) => var(id),
+            Array::Global(id) => global(id),
+        };
+        match self.instr {
+            Instr::Copy { dst, src } => write!(f, "{} = {}", var(dst), operand(src)),
+            Instr::Binary { dst, op, lhs, rhs } => {
+                write!(f, "{} = {} {op} {}", var(dst), operand(lhs), operand(rhs))
+            }
+            Instr::Unary {
+                dst,
+                op,
+                operand: x,
+            } => {
+                write!(f, "{} = {op}{}", var(dst), operand(x))
+            }
+            Instr::GetGlobal { dst, global: g } => write!(f, "{} = {}", var(dst), global(g)),
+            Instr::SetGlobal { global: g, value } => {
+                write!(f, "{} = {}", global(g), operand(value))
+            }
+            Instr::Load {
+                dst,
+                array: a,
+                index,
+            } => write!(f, "{} = {}[{}]", var(dst), array(a), operand(index)),
+            Instr::Store {
+                array: a,
+                index,
+                value,
+            } => write!(f, "{}[{}] = {}", array(a), operand(index), operand(value)),
+            Instr::Clear(a) => write!(f, "clear {}", var(a)),
+            Instr::Label(label) => write!(f, "{label}:"),
+            Instr::Jump(label) => write!(f, "goto {label}"),
+            Instr::Branch {
+                cond,
+                then,
+                otherwise,
+            } => write!(f, "if {} goto {then} else goto {otherwise}", operand(cond)),
+            Instr::Param(value) => write!(f, "param {}", operand(value)),
+            Instr::Call {
+                dst,
+                function,
+                args,
+            } => {
+                if let Some(dst) = dst {
+                    write!(f, "{} = ", var(dst))?;
+                }
+                write!(f, "call {function}, {args}")
+            }
+            Instr::Return(Some(value)) => write!(f, "return {}", operand(value)),
+            Instr::Return(None) => write!(f, "return"),
+            Instr::Print { kind, value } => write!(f, "print {kind} {}", operand(value)),
+            Instr::PrintString(text) => write!(f, "print \"{}\"", text.escape_default()),
+            Instr::Phi { dst, args } => {
+                let args: Vec<String> = args
+                    .iter()
+                    .map(|(label, value)| format!("[{}, {label}]", operand(value)))
+                    .collect();
+                write!(f, "{} = phi {}", var(dst), args.join(", "))
+            }
+        }
+    }
+}
+
+/// The IR of `source`, which must have no errors, optimized at `level`.
+#[cfg(test)]
+pub(crate) fn test_module(source: &str, level: opt::OptLevel) -> Module {
+    let compiled = crate::compile_with(
+        source,
+        crate::diagnostic::Lints::allow_all(),
+        level,
+        |_, _| {},
+    );
+    assert!(
+        !compiled.diagnostics.has_errors(),
+        "{:?}",
+        compiled.diagnostics
+    );
+    compiled.module.unwrap()
+}
+
+/// `function` of `module` in the text form.
+#[cfg(test)]
+pub(crate) fn test_ir(function: &Function, module: &Module) -> String {
+    let mut ir = String::new();
+    function.write(&mut ir, module).unwrap();
+    ir
+}
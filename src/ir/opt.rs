@@ -0,0 +1,343 @@
+//! The optimization passes and the levels that turn them on.
+
+use std::{fmt, str::FromStr};
+
+use super::{copies, dce, fold, gvn, simplify, ssa, verify, Function, Instr, Module};
+
+/// How much to optimize, like `-O0`, `-O1` and `-O2` of C compilers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum OptLevel {
+    /// The IR as it is lowered.
+    O0,
+    /// Folds constants, simplifies the control flow and removes dead code.
+    #[default]
+    O1,
+    /// Also goes through [SSA form](ssa) to remove common subexpressions
+    /// and copies.
+    O2,
+}
+
+impl OptLevel {
+    /// The passes of the level in groups, in the order they run. The passes
+    /// of a group run over and over until none of them changes anything,
+    /// and then the next group runs.
+    pub fn passes(self) -> &'static [&'static [Pass]] {
+        const CLEANUP: &[Pass] = &[Pass::Fold, Pass::SimplifyCfg, Pass::DeadCode];
+        match self {
+            OptLevel::O0 => &[],
+            OptLevel::O1 => &[CLEANUP],
+            OptLevel::O2 => &[
+                CLEANUP,
+                &[Pass::IntoSsa],
+                &[Pass::Gvn, Pass::Hoist, Pass::CopyProp, Pass::DeadCode],
+                &[Pass::OutOfSsa],
+                CLEANUP,
+            ],
+        }
+    }
+}
+
+impl FromStr for OptLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "0" => Ok(OptLevel::O0),
+            "1" => Ok(OptLevel::O1),
+            "2" => Ok(OptLevel::O2),
+            _ => Err(format!("unknown optimization level `{s}`")),
+        }
+    }
+}
+
+impl fmt::Display for OptLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptLevel::O0 => write!(f, "-O0"),
+            OptLevel::O1 => write!(f, "-O1"),
+            OptLevel::O2 => write!(f, "-O2"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pass {
+    /// [`fold::fold`]
+    Fold,
+    /// [`simplify::simplify_cfg`]
+    SimplifyCfg,
+    /// [`dce::eliminate_dead_code`]
+    DeadCode,
+    /// [`ssa::into_ssa`]
+    IntoSsa,
+    /// [`ssa::out_of_ssa`]
+    OutOfSsa,
+    /// [`gvn::number_values`]
+    Gvn,
+    /// [`gvn::hoist`]
+    Hoist,
+    /// [`copies::propagate_copies`]
+    CopyProp,
+}
+
+impl Pass {
+    /// Runs the pass on `function`, returning whether it changed anything.
+    pub fn run(self, function: &mut Function) -> bool {
+        match self {
+            Pass::Fold => fold::fold(function),
+            Pass::SimplifyCfg => simplify::simplify_cfg(function),
+            Pass::DeadCode => dce::eliminate_dead_code(function),
+            Pass::IntoSsa => ssa::into_ssa(function),
+            Pass::OutOfSsa => ssa::out_of_ssa(function),
+            Pass::Gvn => gvn::number_values(function),
+            Pass::Hoist => gvn::hoist(function),
+            Pass::CopyProp => copies::propagate_copies(function),
+        }
+    }
+}
+
+impl fmt::Display for Pass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Pass::Fold => "fold",
+            Pass::SimplifyCfg => "simplify-cfg",
+            Pass::DeadCode => "dce",
+            Pass::IntoSsa => "ssa",
+            Pass::OutOfSsa => "out-of-ssa",
+            Pass::Gvn => "gvn",
+            Pass::Hoist => "hoist",
+            Pass::CopyProp => "copy-prop",
+        })
+    }
+}
+
+/// Runs the [groups of passes](OptLevel::passes) of `level` on every
+/// function of `module`. `after` is called with the module after every pass
+/// that changed it.
+///
+/// Debug builds [verify](verify::verify) every function after every pass,
+/// and panic naming the pass that broke it.
+pub fn optimize(module: &mut Module, level: OptLevel, mut after: impl FnMut(Pass, &Module)) {
+    let mut in_ssa = false;
+    for group in level.passes() {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &pass in *group {
+                in_ssa = match pass {
+                    Pass::IntoSsa => true,
+                    Pass::OutOfSsa => false,
+                    _ => in_ssa,
+                };
+                let mut changed_by_pass = false;
+                for function in &mut module.functions {
+                    changed_by_pass |= pass.run(function);
+                    if cfg!(debug_assertions) {
+                        if let Err(error) = verify::verify(function, in_ssa) {
+                            panic!("`{pass}` broke `{}`: {error}", function.name);
+                        }
+                    }
+                }
+                if changed_by_pass {
+                    after(pass, module);
+                    changed = true;
+                }
+            }
+        }
+    }
+}
+
+/// How many instructions, labels aside, every function has as lowered and
+/// after optimizing.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Stats {
+    pub functions: Vec<FunctionStats>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionStats {
+    pub name: String,
+    pub lowered: usize,
+    pub optimized: usize,
+}
+
+impl Stats {
+    /// Compares the functions of `lowered` with the ones of `optimized`,
+    /// which the passes never add or remove.
+    pub fn new(lowered: &Module, optimized: &Module) -> Self {
+        let count = |function: &Function| {
+            function
+                .body
+                .iter()
+                .filter(|instr| !matches!(instr, Instr::Label(_)))
+                .count()
+        };
+        Stats {
+            functions: lowered
+                .functions
+                .iter()
+                .zip(&optimized.functions)
+                .map(|(lowered, optimized)| FunctionStats {
+                    name: lowered.name.clone(),
+                    lowered: count(lowered),
+                    optimized: count(optimized),
+                })
+                .collect(),
+        }
+    }
+
+    /// The instructions of all the functions, as lowered and optimized.
+    pub fn total(&self) -> (usize, usize) {
+        self.functions
+            .iter()
+            .fold((0, 0), |(lowered, optimized), function| {
+                (lowered + function.lowered, optimized + function.optimized)
+            })
+    }
+}
+
+/// A table with a row for every function and one for the total, and how
+/// much smaller optimizing made each.
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (lowered, optimized) = self.total();
+        let rows: Vec<(&str, usize, usize)> = self
+            .functions
+            .iter()
+            .map(|function| (function.name.as_str(), function.lowered, function.optimized))
+            .chain([("total", lowered, optimized)])
+            .collect();
+        let width = rows
+            .iter()
+            .map(|(name, ..)| name.len())
+            .chain(["function".len()])
+            .max()
+            .unwrap_or_default();
+        writeln!(f, "{:width$}  lowered  optimized  change", "function")?;
+        for (name, lowered, optimized) in rows {
+            let change = if lowered == 0 {
+                0.0
+            } else {
+                (optimized as f64 - lowered as f64) * 100.0 / lowered as f64
+            };
+            writeln!(
+                f,
+                "{name:width$}  {lowered:>7}  {optimized:>9}  {change:>5.0}%"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::Lints;
+
+    const SOURCE: &str = "int main() {\n\
+                          \x20 int n = 4;\n\
+                          \x20 int sum = 0;\n\
+                          \x20 bool debug = false;\n\
+                          \x20 for (int i = 0; i < n; i = i + 1) {\n\
+                          \x20   if (debug) { print(\"i = \", i, \"\\n\"); }\n\
+                          \x20   sum = sum + i * (n / 2);\n\
+                          \x20 }\n\
+                          \x20 int twice = sum * 2;\n\
+                          \x20 if (n % 2 == 0) { print(sum); } else { print(0); }\n\
+                          \x20 return 0;\n\
+                          }";
+
+    /// The IR of [`SOURCE`] at `level`, and the stages it was dumped at.
+    fn compile(level: OptLevel) -> (String, Vec<String>) {
+        let mut stages = vec![];
+        let compiled = crate::compile_with(SOURCE, Lints::allow_all(), level, |pass, _| {
+            stages.push(pass.map_or("lowered".to_owned(), |pass| pass.to_string()));
+        });
+        (compiled.module.unwrap().to_string(), stages)
+    }
+
+    #[test]
+    fn optimizes_until_nothing_changes() {
+        let (ir, stages) = compile(OptLevel::O1);
+        assert_eq!(stages, ["lowered", "fold", "simplify-cfg", "dce"]);
+        // `twice` might overflow, so it stays.
+        assert_eq!(
+            ir,
+            "function main() {\n\
+             \x20   sum = 0\n\
+             \x20   i = 0\n\
+             L0:\n\
+             \x20   t0 = i < 4\n\
+             \x20   if t0 goto L1 else goto L2\n\
+             L1:\n\
+             \x20   t2 = i * 2\n\
+             \x20   sum = sum + t2\n\
+             \x20   i = i + 1\n\
+             \x20   goto L0\n\
+             L2:\n\
+             \x20   twice = sum * 2\n\
+             \x20   print int sum\n\
+             \x20   return 0\n\
+             }\n"
+        );
+
+        // The constants `sum` and `i` start out as go into the phis of the
+        // loop, so they are named after the phis when they come out again.
+        let (ir, stages) = compile(OptLevel::O2);
+        assert_eq!(
+            stages,
+            [
+                "lowered",
+                "fold",
+                "simplify-cfg",
+                "dce",
+                "ssa",
+                "copy-prop",
+                "out-of-ssa",
+                "simplify-cfg"
+            ]
+        );
+        assert_eq!(
+            ir,
+            "function main() {\n\
+             \x20   sum.1 = 0\n\
+             \x20   i.1 = 0\n\
+             L0:\n\
+             \x20   t0 = i.1 < 4\n\
+             \x20   if t0 goto L1 else goto L2\n\
+             L1:\n\
+             \x20   t2 = i.1 * 2\n\
+             \x20   sum.1 = sum.1 + t2\n\
+             \x20   i.1 = i.1 + 1\n\
+             \x20   goto L0\n\
+             L2:\n\
+             \x20   twice = sum.1 * 2\n\
+             \x20   print int sum.1\n\
+             \x20   return 0\n\
+             }\n"
+        );
+
+        let (ir, stages) = compile(OptLevel::O0);
+        assert_eq!(stages, ["lowered"]);
+        assert!(ir.contains("print \"i = \""));
+    }
+
+    #[test]
+    fn counts_instructions_before_and_after() {
+        let mut lowered = None;
+        let compiled =
+            crate::compile_with(SOURCE, Lints::allow_all(), OptLevel::O2, |pass, module| {
+                if pass.is_none() {
+                    lowered = Some(module.clone());
+                }
+            });
+        let stats = Stats::new(&lowered.unwrap(), &compiled.module.unwrap());
+        assert_eq!(stats.total(), (23, 11));
+        assert_eq!(
+            stats.to_string(),
+            "function  lowered  optimized  change\n\
+             main           23         11    -52%\n\
+             total          23         11    -52%\n"
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Control-flow simplification.
+//!
+//! Jumps and branches to a block that only jumps on go straight to where
+//! it jumps, a branch with the same label on both sides becomes a jump,
+//! blocks that cannot be reached from the entry are removed, and a block
+//! with a single predecessor that jumps to it is merged into it. The labels
+//! that are left are numbered again.
+
+use std::collections::HashMap;
+
+use super::{
+    cfg::{BlockId, Cfg},
+    Function, Instr, Label,
+};
+
+/// Simplifies the control flow of `function`, returning whether anything
+/// changed.
+pub fn simplify_cfg(function: &mut Function) -> bool {
+    let mut cfg = Cfg::new(function);
+    let mut changed = thread_jumps(&mut cfg);
+    changed |= cfg.remove_unreachable();
+    changed |= merge_blocks(&mut cfg);
+    if changed {
+        cfg.write_to(function);
+        function.number_labels();
+    }
+    changed
+}
+
+/// Points the jumps and branches to a block that is only a `goto` at the
+/// end of the chain of such blocks.
+fn thread_jumps(cfg: &mut Cfg) -> bool {
+    let forwards: HashMap<Label, Label> = cfg
+        .blocks
+        .iter()
+        .skip(1)
+        .filter_map(|block| match block.instrs[..] {
+            [Instr::Jump(target)] => Some((block.label, target)),
+            _ => None,
+        })
+        .collect();
+    // Stops at a chain that loops, like the one of `for (;;) {}`.
+    let end = |mut label: Label| {
+        let mut seen = vec![label];
+        while let Some(&next) = forwards.get(&label) {
+            if seen.contains(&next) {
+                break;
+            }
+            seen.push(next);
+            label = next;
+        }
+        label
+    };
+    let mut changed = false;
+    for block in &mut cfg.blocks {
+        let Some(last) = block.instrs.last_mut() else {
+            continue;
+        };
+        let threaded = match last {
+            Instr::Jump(target) => Instr::Jump(end(*target)),
+            Instr::Branch {
+                cond,
+                then,
+                otherwise,
+            } => match (end(*then), end(*otherwise)) {
+                (then, otherwise) if then == otherwise => Instr::Jump(then),
+                (then, otherwise) => Instr::Branch {
+                    cond: *cond,
+                    then,
+                    otherwise,
+                },
+            },
+            _ => continue,
+        };
+        if *last != threaded {
+            *last = threaded;
+            changed = true;
+        }
+    }
+    if changed {
+        cfg.connect();
+    }
+    changed
+}
+
+/// Merges every block whose only predecessor jumps to it into that
+/// predecessor.
+fn merge_blocks(cfg: &mut Cfg) -> bool {
+    let mut changed = false;
+    while let Some(i) = (1..cfg.blocks.len()).find(|&i| {
+        let block = &cfg.blocks[i];
+        match block.preds[..] {
+            [pred] => {
+                pred != BlockId(i)
+                    && cfg.blocks[pred.0].instrs.last() == Some(&Instr::Jump(block.label))
+            }
+            _ => false,
+        }
+    }) {
+        let block = cfg.blocks.remove(i);
+        let pred = &mut cfg.blocks[block.preds[0].0 - usize::from(block.preds[0].0 > i)];
+        pred.instrs.pop();
+        pred.instrs.extend(block.instrs);
+        cfg.connect();
+        changed = true;
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{opt::OptLevel, test_ir, test_module};
+
+    #[test]
+    fn threads_jumps_and_removes_unreachable_blocks() {
+        let module = test_module(
+            "int main() {\n\
+             \x20 int x = 0;\n\
+             \x20 for (int i = 0; i < 3; i = i + 1) {\n\
+             \x20   if (i == 1) { continue; } else if (i == 2) { x = 1; } else { }\n\
+             \x20 }\n\
+             \x20 if (x > 0) { return x; } else { return 0; }\n\
+             \x20 print(x);\n\
+             \x20 for (;;) { }\n\
+             }",
+            OptLevel::O0,
+        );
+        let mut main = module.functions[0].clone();
+        assert!(simplify_cfg(&mut main));
+        let ir = test_ir(&main, &module);
+        // The empty `else` and the `continue` go straight to the update, and
+        // nothing after the `if` that returns is left.
+        assert_eq!(
+            ir,
+            "function main() {\n\
+             \x20   x = 0\n\
+             \x20   i = 0\n\
+             L0:\n\
+             \x20   t0 = i < 3\n\
+             \x20   if t0 goto L1 else goto L5\n\
+             L1:\n\
+             \x20   t1 = i == 1\n\
+             \x20   if t1 goto L4 else goto L2\n\
+             L2:\n\
+             \x20   t2 = i == 2\n\
+             \x20   if t2 goto L3 else goto L4\n\
+             L3:\n\
+             \x20   x = 1\n\
+             L4:\n\
+             \x20   i = i + 1\n\
+             \x20   goto L0\n\
+             L5:\n\
+             \x20   t3 = x > 0\n\
+             \x20   if t3 goto L6 else goto L7\n\
+             L6:\n\
+             \x20   return x\n\
+             L7:\n\
+             \x20   return 0\n\
+             }\n"
+        );
+        assert!(!simplify_cfg(&mut main));
+    }
+}
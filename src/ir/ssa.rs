@@ -0,0 +1,579 @@
+//! Static single assignment form.
+//!
+//! In SSA form every variable is written by exactly one instruction, or is
+//! a parameter, and that instruction dominates every read of it. Where
+//! different assignments to a source variable meet, after an `if` or at the
+//! top of a loop, a [phi](Instr::Phi) picks the one control came through.
+//!
+//! [`into_ssa`] places phis with the dominance frontiers of the blocks
+//! that assign a variable, only where the variable is live (pruned SSA, as
+//! in Cytron et al., "Efficiently Computing Static Single Assignment Form
+//! and the Control Dependence Graph"), and then renames every assignment
+//! after the first to a new version, `x.1`, `x.2`, ... walking the
+//! dominator tree. [`out_of_ssa`] removes the phis again: a phi and its
+//! arguments share one variable when none of them is live where another is
+//! written, and the other arguments are copied at the end of the
+//! predecessor they come from.
+
+use std::collections::HashSet;
+
+use super::{
+    cfg::{BlockId, Cfg},
+    dom::Dominators,
+    liveness::{read, Liveness},
+    Function, Instr, Label, Operand, Var, VarId, VarKind,
+};
+
+/// Puts `function` into SSA form, returning whether anything changed. The
+/// blocks that cannot be reached are removed first.
+///
+/// A variable that might be read before it is written, which checked
+/// programs only do with arrays, reads `0` there.
+pub fn into_ssa(function: &mut Function) -> bool {
+    let mut cfg = Cfg::new(function);
+    let mut changed = cfg.remove_unreachable();
+    let dominators = Dominators::new(&cfg);
+    let frontiers = dominators.frontiers(&cfg);
+    let liveness = Liveness::new(&cfg, function.vars.len());
+
+    // The blocks that write each variable.
+    let mut defs: Vec<Vec<BlockId>> = vec![vec![]; function.vars.len()];
+    for param in &function.params {
+        defs[param.0].push(Cfg::ENTRY);
+    }
+    for (i, block) in cfg.blocks.iter().enumerate() {
+        for dst in block.instrs.iter().filter_map(Instr::def) {
+            if !defs[dst.0].contains(&BlockId(i)) {
+                defs[dst.0].push(BlockId(i));
+            }
+        }
+    }
+    // The variable of each phi of each block, in order.
+    let mut phis: Vec<Vec<VarId>> = vec![vec![]; cfg.blocks.len()];
+    for (var, blocks) in defs.iter().enumerate() {
+        let mut work = blocks.clone();
+        while let Some(block) = work.pop() {
+            for &frontier in &frontiers[block.0] {
+                if phis[frontier.0].contains(&VarId(var)) || !liveness.live_in[frontier.0][var] {
+                    continue;
+                }
+                phis[frontier.0].push(VarId(var));
+                if !blocks.contains(&frontier) {
+                    work.push(frontier);
+                }
+            }
+        }
+    }
+    for (i, vars) in phis.iter().enumerate() {
+        let block = &cfg.blocks[i];
+        let new: Vec<Instr> = vars
+            .iter()
+            .map(|&var| Instr::Phi {
+                dst: var,
+                args: block
+                    .preds
+                    .iter()
+                    .map(|pred| (cfg.blocks[pred.0].label, Operand::Var(var)))
+                    .collect(),
+            })
+            .collect();
+        changed |= !new.is_empty();
+        cfg.blocks[i].instrs.splice(0..0, new);
+    }
+
+    let mut renamer = Renamer {
+        stacks: vec![vec![]; function.vars.len()],
+        written: vec![false; function.vars.len()],
+        changed: false,
+    };
+    for &param in &function.params {
+        renamer.stacks[param.0].push(param);
+        renamer.written[param.0] = true;
+    }
+    renamer.rename(Cfg::ENTRY, &mut cfg, &dominators, &phis, function);
+    changed |= renamer.changed;
+    if changed {
+        cfg.write_to(function);
+        function.number_labels();
+    }
+    changed
+}
+
+struct Renamer {
+    /// The current version of every variable of the source, innermost last.
+    stacks: Vec<Vec<VarId>>,
+    /// Whether a variable has been written already, so that the next write
+    /// needs a new version.
+    written: Vec<bool>,
+    changed: bool,
+}
+
+impl Renamer {
+    /// Renames the variables of `block` and of the blocks it dominates.
+    fn rename(
+        &mut self,
+        block: BlockId,
+        cfg: &mut Cfg,
+        dominators: &Dominators,
+        phis: &[Vec<VarId>],
+        function: &mut Function,
+    ) {
+        let mut pushed = vec![];
+        for instr in &mut cfg.blocks[block.0].instrs {
+            if !matches!(instr, Instr::Phi { .. }) {
+                for operand in instr.uses_mut() {
+                    if let Operand::Var(var) = operand {
+                        *operand = self.current(*var);
+                    }
+                }
+            }
+            if let Some(dst) = instr.def_mut() {
+                let var = *dst;
+                *dst = self.version(var, function);
+                self.stacks[var.0].push(*dst);
+                pushed.push(var);
+            }
+        }
+        let label = cfg.blocks[block.0].label;
+        for succ in cfg.blocks[block.0].succs.clone() {
+            for (instr, &var) in cfg.blocks[succ.0].instrs.iter_mut().zip(&phis[succ.0]) {
+                let Instr::Phi { args, .. } = instr else {
+                    unreachable!("the phis are at the top");
+                };
+                for (from, value) in args {
+                    if *from == label {
+                        *value = self.current(var);
+                    }
+                }
+            }
+        }
+        for &child in dominators.children(block) {
+            self.rename(child, cfg, dominators, phis, function);
+        }
+        for var in pushed {
+            self.stacks[var.0].pop();
+        }
+    }
+
+    /// The version of `var` that reaches the current point.
+    fn current(&self, var: VarId) -> Operand {
+        self.stacks[var.0]
+            .last()
+            .map_or(Operand::Const(0), |&version| Operand::Var(version))
+    }
+
+    /// A variable for a new write of `var`: `var` itself the first time.
+    fn version(&mut self, var: VarId, function: &mut Function) -> VarId {
+        if !self.written[var.0] {
+            self.written[var.0] = true;
+            return var;
+        }
+        self.changed = true;
+        let Var { name, kind } = function.vars[var.0].clone();
+        let kind = match kind {
+            VarKind::Param => VarKind::Local,
+            kind => kind,
+        };
+        function.new_var(name, kind)
+    }
+}
+
+/// Takes `function` out of SSA form, returning whether anything changed.
+pub fn out_of_ssa(function: &mut Function) -> bool {
+    let mut cfg = Cfg::new(function);
+    if !cfg
+        .blocks
+        .iter()
+        .any(|block| matches!(block.instrs.first(), Some(Instr::Phi { .. })))
+    {
+        return false;
+    }
+    split_edges(&mut cfg);
+
+    // Every variable starts out in a web of its own. A phi joins the webs of
+    // its arguments that do not interfere with its own.
+    let interference = interference(&cfg, function.vars.len());
+    let mut webs = Webs::new(function.vars.len());
+    for instr in cfg.blocks.iter().flat_map(|block| &block.instrs) {
+        let Instr::Phi { dst, args } = instr else {
+            continue;
+        };
+        for (_, value) in args {
+            if let Operand::Var(var) = value {
+                webs.join(*dst, *var, &interference);
+            }
+        }
+    }
+    for instr in cfg.blocks.iter_mut().flat_map(|block| &mut block.instrs) {
+        for operand in instr.uses_mut() {
+            if let Operand::Var(var) = operand {
+                *var = webs.name(*var);
+            }
+        }
+        if let Some(dst) = instr.def_mut() {
+            *dst = webs.name(*dst);
+        }
+    }
+
+    for i in 0..cfg.blocks.len() {
+        let phis: Vec<(VarId, Vec<(Label, Operand)>)> = cfg.blocks[i]
+            .instrs
+            .iter()
+            .map_while(|instr| match instr {
+                Instr::Phi { dst, args } => Some((*dst, args.clone())),
+                _ => None,
+            })
+            .collect();
+        cfg.blocks[i].instrs.drain(..phis.len());
+        let preds = cfg.blocks[i].preds.clone();
+        for pred in preds {
+            let label = cfg.blocks[pred.0].label;
+            let copies = phis
+                .iter()
+                .map(|(dst, args)| {
+                    let (_, value) = args
+                        .iter()
+                        .find(|(from, _)| *from == label)
+                        .expect("a phi has an argument for every predecessor");
+                    (*dst, *value)
+                })
+                .collect();
+            let copies = sequentialize(copies, function);
+            let instrs = &mut cfg.blocks[pred.0].instrs;
+            let end = instrs.len() - 1;
+            instrs.splice(end..end, copies);
+        }
+    }
+    cfg.write_to(function);
+    function.number_labels();
+    true
+}
+
+/// Gives every edge into a block with phis from a block that does not end
+/// in a jump its own block, where the copies for the phis can go.
+fn split_edges(cfg: &mut Cfg) {
+    for i in 0..cfg.blocks.len() {
+        if !matches!(cfg.blocks[i].instrs.first(), Some(Instr::Phi { .. })) {
+            continue;
+        }
+        let label = cfg.blocks[i].label;
+        for pred in cfg.blocks[i].preds.clone() {
+            if matches!(cfg.blocks[pred.0].instrs.last(), Some(Instr::Jump(_))) {
+                continue;
+            }
+            let edge = cfg.new_block();
+            let via = cfg.blocks[edge.0].label;
+            cfg.blocks[edge.0].instrs.push(Instr::Jump(label));
+            let from = cfg.blocks[pred.0].label;
+            if let Some(Instr::Branch {
+                then, otherwise, ..
+            }) = cfg.blocks[pred.0].instrs.last_mut()
+            {
+                for target in [then, otherwise] {
+                    if *target == label {
+                        *target = via;
+                    }
+                }
+            }
+            for instr in &mut cfg.blocks[i].instrs {
+                if let Instr::Phi { args, .. } = instr {
+                    for (arg, _) in args {
+                        if *arg == from {
+                            *arg = via;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    cfg.connect();
+}
+
+/// The variables live where each variable is written, by index.
+fn interference(cfg: &Cfg, vars: usize) -> Vec<HashSet<usize>> {
+    let liveness = Liveness::new(cfg, vars);
+    let mut interference = vec![HashSet::new(); vars];
+    let mut interfere = |dst: VarId, live: &[bool]| {
+        for var in (0..vars).filter(|&var| live[var] && var != dst.0) {
+            interference[dst.0].insert(var);
+            interference[var].insert(dst.0);
+        }
+    };
+    for (i, block) in cfg.blocks.iter().enumerate() {
+        let mut live = liveness.live_out(BlockId(i)).to_vec();
+        let mut phis = vec![];
+        for instr in block.instrs.iter().rev() {
+            if let Instr::Phi { dst, .. } = instr {
+                phis.push(*dst);
+                continue;
+            }
+            if let Some(dst) = instr.def() {
+                interfere(dst, &live);
+                live[dst.0] = false;
+            }
+            for var in read(instr) {
+                live[var] = true;
+            }
+        }
+        // The phis all write at the top of the block, at once, so they
+        // interfere with each other even when some are never read.
+        for &dst in &phis {
+            live[dst.0] = true;
+        }
+        for &dst in &phis {
+            interfere(dst, &live);
+        }
+    }
+    interference
+}
+
+/// Sets of variables that share a name out of SSA form.
+struct Webs {
+    /// The web of every variable, by the variable that names it.
+    web: Vec<VarId>,
+    members: Vec<Vec<VarId>>,
+}
+
+impl Webs {
+    fn new(vars: usize) -> Self {
+        Webs {
+            web: (0..vars).map(VarId).collect(),
+            members: (0..vars).map(|var| vec![VarId(var)]).collect(),
+        }
+    }
+
+    /// The variable `var` is renamed to, the first of its web.
+    fn name(&self, var: VarId) -> VarId {
+        self.web[var.0]
+    }
+
+    /// Joins the webs of `a` and `b` unless a variable of one interferes
+    /// with a variable of the other.
+    fn join(&mut self, a: VarId, b: VarId, interference: &[HashSet<usize>]) {
+        let (a, b) = (self.web[a.0], self.web[b.0]);
+        if a == b
+            || self.members[a.0].iter().any(|x| {
+                self.members[b.0]
+                    .iter()
+                    .any(|y| interference[x.0].contains(&y.0))
+            })
+        {
+            return;
+        }
+        let (keep, gone) = (a.min(b), a.max(b));
+        let members = std::mem::take(&mut self.members[gone.0]);
+        for &member in &members {
+            self.web[member.0] = keep;
+        }
+        self.members[keep.0].extend(members);
+    }
+}
+
+/// Orders copies that happen at once, `dst = value` each, so that none
+/// writes a variable another still has to read, going through a new
+/// temporary of `function` to break cycles like `a = b, b = a`.
+fn sequentialize(mut copies: Vec<(VarId, Operand)>, function: &mut Function) -> Vec<Instr> {
+    copies.retain(|&(dst, value)| value != Operand::Var(dst));
+    let mut instrs = vec![];
+    while !copies.is_empty() {
+        let read = |dst: VarId, copies: &[(VarId, Operand)]| {
+            copies.iter().any(|&(_, value)| value == Operand::Var(dst))
+        };
+        match copies.iter().position(|&(dst, _)| !read(dst, &copies)) {
+            Some(i) => {
+                let (dst, src) = copies.remove(i);
+                instrs.push(Instr::Copy { dst, src });
+            }
+            None => {
+                // Every variable written is also read, so the copies are
+                // cycles. Saving one of them frees it to be written.
+                let (dst, _) = copies[0];
+                let temp = function.new_temp();
+                instrs.push(Instr::Copy {
+                    dst: temp,
+                    src: Operand::Var(dst),
+                });
+                for (_, value) in &mut copies {
+                    if *value == Operand::Var(dst) {
+                        *value = Operand::Var(temp);
+                    }
+                }
+            }
+        }
+    }
+    instrs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{opt::OptLevel, test_ir, test_module, verify::verify, Module};
+
+    #[test]
+    fn places_phis_where_assignments_meet() {
+        let module = test_module(
+            "int f(int n) {\n\
+             \x20 int x = 0;\n\
+             \x20 for (int i = 0; i < n; i = i + 1) {\n\
+             \x20   if (i % 2 == 0) { x = x + i; } else { x = x - 1; }\n\
+             \x20 }\n\
+             \x20 return x;\n\
+             }\n\
+             int main() { return f(3); }",
+            OptLevel::O1,
+        );
+        let mut f = module.functions[0].clone();
+        assert!(into_ssa(&mut f));
+        assert_eq!(verify(&f, true), Ok(()));
+        assert_eq!(
+            test_ir(&f, &module),
+            "function f(n) {\n\
+             L0:\n\
+             \x20   x = 0\n\
+             \x20   i = 0\n\
+             L1:\n\
+             \x20   x.1 = phi [x, L0], [x.4, L5]\n\
+             \x20   i.1 = phi [i, L0], [i.2, L5]\n\
+             \x20   t0 = i.1 < n\n\
+             \x20   if t0 goto L2 else goto L6\n\
+             L2:\n\
+             \x20   t1 = i.1 % 2\n\
+             \x20   t2 = t1 == 0\n\
+             \x20   if t2 goto L3 else goto L4\n\
+             L3:\n\
+             \x20   x.2 = x.1 + i.1\n\
+             \x20   goto L5\n\
+             L4:\n\
+             \x20   x.3 = x.1 - 1\n\
+             L5:\n\
+             \x20   x.4 = phi [x.2, L3], [x.3, L4]\n\
+             \x20   i.2 = i.1 + 1\n\
+             \x20   goto L1\n\
+             L6:\n\
+             \x20   return x.1\n\
+             }\n"
+        );
+        assert!(!into_ssa(&mut f));
+
+        assert!(out_of_ssa(&mut f));
+        assert_eq!(verify(&f, false), Ok(()));
+        // Every version of `x` and `i` gets its name back, so there is
+        // nothing to copy.
+        assert_eq!(
+            test_ir(&f, &module),
+            "function f(n) {\n\
+             L0:\n\
+             \x20   x = 0\n\
+             \x20   i = 0\n\
+             L1:\n\
+             \x20   t0 = i < n\n\
+             \x20   if t0 goto L2 else goto L6\n\
+             L2:\n\
+             \x20   t1 = i % 2\n\
+             \x20   t2 = t1 == 0\n\
+             \x20   if t2 goto L3 else goto L4\n\
+             L3:\n\
+             \x20   x = x + i\n\
+             \x20   goto L5\n\
+             L4:\n\
+             \x20   x = x - 1\n\
+             L5:\n\
+             \x20   i = i + 1\n\
+             \x20   goto L1\n\
+             L6:\n\
+             \x20   return x\n\
+             }\n"
+        );
+        assert!(!out_of_ssa(&mut f));
+    }
+
+    #[test]
+    fn copies_for_phis_that_interfere() {
+        let module = test_module(
+            "int main() {\n\
+             \x20 int x = 1;\n\
+             \x20 int y = 2;\n\
+             \x20 for (int i = 0; i < 3; i = i + 1) { int t = x; x = y; y = t; }\n\
+             \x20 print(x, y);\n\
+             \x20 return 0;\n\
+             }",
+            OptLevel::O1,
+        );
+        let mut main = module.functions[0].clone();
+        assert!(into_ssa(&mut main));
+        // Reads the sources of the copies instead, which leaves `x` and `y`
+        // swapping at the top of the loop.
+        let mut copies = vec![None; main.vars.len()];
+        main.body.retain(|instr| match instr {
+            Instr::Copy {
+                dst,
+                src: Operand::Var(src),
+            } if main.vars[dst.0].name == "t" => {
+                copies[dst.0] = Some(*src);
+                false
+            }
+            _ => true,
+        });
+        for operand in main.body.iter_mut().flat_map(Instr::uses_mut) {
+            if let Operand::Var(var) = operand {
+                if let Some(src) = copies[var.0] {
+                    *var = src;
+                }
+            }
+        }
+        assert_eq!(verify(&main, true), Ok(()));
+
+        assert!(out_of_ssa(&mut main));
+        assert_eq!(verify(&main, false), Ok(()));
+        // `x.2` is written while `x.1` is still to be read, so it keeps a
+        // variable of its own and is copied to `x` at the end of the loop.
+        assert_eq!(
+            test_ir(&main, &module),
+            "function main() {\n\
+             L0:\n\
+             \x20   x = 1\n\
+             \x20   y = 2\n\
+             \x20   i = 0\n\
+             L1:\n\
+             \x20   t0 = i < 3\n\
+             \x20   if t0 goto L2 else goto L3\n\
+             L2:\n\
+             \x20   x.2 = y\n\
+             \x20   y = x\n\
+             \x20   i = i + 1\n\
+             \x20   x = x.2\n\
+             \x20   goto L1\n\
+             L3:\n\
+             \x20   print int x\n\
+             \x20   print int y\n\
+             \x20   return 0\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn breaks_cycles_of_copies_with_a_temporary() {
+        let mut function = Function::new("f");
+        let a = function.new_var("a", VarKind::Local);
+        let b = function.new_var("b", VarKind::Local);
+        let c = function.new_var("c", VarKind::Local);
+        let copies = sequentialize(
+            vec![
+                (a, Operand::Var(b)),
+                (b, Operand::Var(a)),
+                (c, Operand::Var(a)),
+            ],
+            &mut function,
+        );
+        function.body = copies;
+        assert_eq!(
+            test_ir(&function, &Module::default()),
+            "function f() {\n\
+             \x20   c = a\n\
+             \x20   t0 = a\n\
+             \x20   a = b\n\
+             \x20   b = t0\n\
+             }\n"
+        );
+    }
+}
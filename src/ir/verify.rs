@@ -0,0 +1,274 @@
+//! Checks that a function is well formed, to catch a pass that breaks the
+//! IR right after it runs instead of in whatever reads the IR next.
+
+use std::collections::HashSet;
+
+use super::{
+    cfg::{BlockId, Cfg},
+    dom::Dominators,
+    Array, Function, Instr, Label, Operand, VarId, VarKind,
+};
+
+/// Checks `function`, and that it is in [SSA form](super::ssa) when `ssa`
+/// is set, returning what is wrong with it.
+///
+/// Every label has to be placed once and every jump has to go to one,
+/// variables have to exist and be used as what they are, scalars or
+/// arrays, and [phis](Instr::Phi) can only be at the top of a block, with
+/// an argument for each predecessor. Outside SSA form there are no phis.
+/// In SSA form every variable is written once, or is a parameter, and the
+/// write dominates every read, or the end of the predecessor a phi reads it
+/// from.
+pub fn verify(function: &Function, ssa: bool) -> Result<(), String> {
+    let name = |var: VarId| &function.vars[var.0].name;
+    let mut placed = HashSet::new();
+    for instr in &function.body {
+        if let Instr::Label(label) = instr {
+            if !placed.insert(*label) {
+                return Err(format!("`{label}` is placed twice"));
+            }
+        }
+    }
+    for instr in &function.body {
+        let mut labels = instr.targets();
+        if let Instr::Phi { args, .. } = instr {
+            labels.extend(args.iter().map(|(label, _)| *label));
+        }
+        if let Some(label) = labels.iter().find(|label| !placed.contains(label)) {
+            return Err(format!("`{label}` is not placed"));
+        }
+        let vars: Vec<VarId> = instr
+            .uses()
+            .into_iter()
+            .filter_map(|operand| match operand {
+                Operand::Var(var) => Some(var),
+                Operand::Const(_) => None,
+            })
+            .chain(instr.def())
+            .collect();
+        if let Some(var) = vars.iter().find(|var| var.0 >= function.vars.len()) {
+            return Err(format!("variable {} does not exist", var.0));
+        }
+        if let Some(&var) = vars
+            .iter()
+            .find(|var| matches!(function.vars[var.0].kind, VarKind::Array(_)))
+        {
+            return Err(format!("the array `{}` is used as a scalar", name(var)));
+        }
+        let array = match instr {
+            Instr::Load {
+                array: Array::Local(array),
+                ..
+            }
+            | Instr::Store {
+                array: Array::Local(array),
+                ..
+            }
+            | Instr::Clear(array) => Some(*array),
+            _ => None,
+        };
+        if let Some(array) = array {
+            match function.vars.get(array.0) {
+                Some(var) if matches!(var.kind, VarKind::Array(_)) => {}
+                Some(_) => return Err(format!("`{}` is not an array", name(array))),
+                None => return Err(format!("variable {} does not exist", array.0)),
+            }
+        }
+    }
+
+    let cfg = Cfg::new(function);
+    for block in &cfg.blocks {
+        let phis = block
+            .instrs
+            .iter()
+            .take_while(|instr| matches!(instr, Instr::Phi { .. }))
+            .count();
+        if let Some(Instr::Phi { dst, .. }) = block.instrs[phis..]
+            .iter()
+            .find(|instr| matches!(instr, Instr::Phi { .. }))
+        {
+            return Err(format!(
+                "the phi of `{}` is not at the top of `{}`",
+                name(*dst),
+                block.label
+            ));
+        }
+        if phis > 0 && !ssa {
+            return Err(format!("`{}` has phis outside SSA form", block.label));
+        }
+        let mut preds: Vec<Label> = block
+            .preds
+            .iter()
+            .map(|pred| cfg.blocks[pred.0].label)
+            .collect();
+        preds.sort();
+        for instr in &block.instrs[..phis] {
+            let Instr::Phi { dst, args } = instr else {
+                unreachable!("counted above");
+            };
+            let mut labels: Vec<Label> = args.iter().map(|(label, _)| *label).collect();
+            labels.sort();
+            if labels != preds {
+                return Err(format!(
+                    "the phi of `{}` in `{}` does not have one argument for each predecessor",
+                    name(*dst),
+                    block.label
+                ));
+            }
+        }
+    }
+    if ssa {
+        verify_ssa(function, &cfg)?;
+    }
+    Ok(())
+}
+
+/// Checks that every variable is written once and the write dominates the
+/// reads.
+fn verify_ssa(function: &Function, cfg: &Cfg) -> Result<(), String> {
+    let name = |var: VarId| &function.vars[var.0].name;
+    // Where every variable is written, as the block and the index of the
+    // instruction, with the parameters before the first instruction.
+    let mut defs: Vec<Option<(BlockId, Option<usize>)>> = vec![None; function.vars.len()];
+    for &param in &function.params {
+        defs[param.0] = Some((Cfg::ENTRY, None));
+    }
+    for (i, block) in cfg.blocks.iter().enumerate() {
+        for (j, instr) in block.instrs.iter().enumerate() {
+            if let Some(dst) = instr.def() {
+                if defs[dst.0].is_some() {
+                    return Err(format!("`{}` is written more than once", name(dst)));
+                }
+                defs[dst.0] = Some((BlockId(i), Some(j)));
+            }
+        }
+    }
+    let dominators = Dominators::new(cfg);
+    // Whether the write of `var` happens before the instruction at `at` of
+    // `block`, or at its end when `at` is `None`.
+    let reaches = |var: VarId, block: BlockId, at: Option<usize>| match defs[var.0] {
+        None => false,
+        Some((def, index)) if def == block => match (index, at) {
+            (None, _) => true,
+            (Some(_), None) => true,
+            (Some(index), Some(at)) => index < at,
+        },
+        Some((def, _)) => dominators.dominates(def, block),
+    };
+    for (i, block) in cfg.blocks.iter().enumerate() {
+        if !dominators.is_reachable(BlockId(i)) {
+            continue;
+        }
+        for (j, instr) in block.instrs.iter().enumerate() {
+            let reads: Vec<(VarId, BlockId, Option<usize>)> = match instr {
+                Instr::Phi { args, .. } => args
+                    .iter()
+                    .filter_map(|(label, value)| match value {
+                        Operand::Var(var) => {
+                            Some((*var, cfg.block_of(*label).expect("checked"), None))
+                        }
+                        Operand::Const(_) => None,
+                    })
+                    .collect(),
+                _ => instr
+                    .uses()
+                    .into_iter()
+                    .filter_map(|operand| match operand {
+                        Operand::Var(var) => Some((var, BlockId(i), Some(j))),
+                        Operand::Const(_) => None,
+                    })
+                    .collect(),
+            };
+            if let Some((var, _, _)) = reads
+                .into_iter()
+                .find(|&(var, block, at)| !reaches(var, block, at))
+            {
+                return Err(format!(
+                    "`{}` is read in `{}` where it might not be written",
+                    name(var),
+                    block.label
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{opt::OptLevel, ssa::into_ssa, test_module};
+
+    #[test]
+    fn rejects_broken_functions() {
+        let module = test_module(
+            "int main() {\n\
+             \x20 int x = 0;\n\
+             \x20 for (int i = 0; i < 3; i = i + 1) { x = x + i; }\n\
+             \x20 return x;\n\
+             }",
+            OptLevel::O1,
+        );
+        let main = module.functions[0].clone();
+        assert_eq!(verify(&main, false), Ok(()));
+        // `x` and `i` are written twice.
+        assert_eq!(
+            verify(&main, true),
+            Err("`x` is written more than once".to_owned())
+        );
+        let mut ssa = main.clone();
+        into_ssa(&mut ssa);
+        assert_eq!(verify(&ssa, true), Ok(()));
+        assert_eq!(
+            verify(&ssa, false),
+            Err("`L1` has phis outside SSA form".to_owned())
+        );
+
+        let with = |change: &dyn Fn(&mut Function)| {
+            let mut function = ssa.clone();
+            change(&mut function);
+            verify(&function, true)
+        };
+        let x = |function: &Function, name: &str| {
+            VarId(
+                function
+                    .vars
+                    .iter()
+                    .position(|var| var.name == name)
+                    .unwrap(),
+            )
+        };
+        // Returns the version of `x` written in the loop after it.
+        assert_eq!(
+            with(&|f| {
+                let last = f.body.len() - 1;
+                f.body[last] = Instr::Return(Some(Operand::Var(x(f, "x.2"))));
+            }),
+            Err("`x.2` is read in `L3` where it might not be written".to_owned())
+        );
+        assert_eq!(
+            // Moves the phi of `x` below the comparison.
+            with(&|f| {
+                let phi = f.body.remove(4);
+                f.body.insert(6, phi);
+            }),
+            Err("the phi of `x.1` is not at the top of `L1`".to_owned())
+        );
+        assert_eq!(
+            with(&|f| {
+                let Instr::Phi { args, .. } = &mut f.body[4] else {
+                    panic!("{:?} is not a phi", f.body[4]);
+                };
+                args.pop();
+            }),
+            Err(
+                "the phi of `x.1` in `L1` does not have one argument for each predecessor"
+                    .to_owned()
+            )
+        );
+        assert_eq!(
+            with(&|f| f.body.push(Instr::Jump(Label(9)))),
+            Err("`L9` is not placed".to_owned())
+        );
+    }
+}
@@ -0,0 +1,584 @@
+//! A minimal JSON value and the JSON form of the compiler's data.
+//!
+//! Every document written by `--format json` is a single line with these
+//! fields, in this order:
+//!
+//! ```text
+//! { "version": 1, "file": string, <command fields>, "diagnostics": [Diagnostic] }
+//! ```
+//!
+//! where the command fields are `"tokens": [Token]` for `lex`,
+//! `"tree": Node | null` for `parse`, `"ast": Program | null` for `ast`,
+//! `"symbols": [Symbol]` for `check`, `"ir": string | null` for `ir`, with
+//! the IR in its text form and, with `--dump-passes`, followed by
+//! `"passes": [Stage]`, `"cfg": string | null` for `cfg`, with the
+//! Graphviz graph, both followed by `"stats": Stats | null` with `--stats`,
+//! `"run": Run` for `run` and
+//! `"grammar": Grammar | null` for `grammar`.
+//!
+//! ```text
+//! Span       { "start": int, "end": int, "line": int, "column": int }
+//! Token      { "type": "T_Id", "literal": string, "span": Span }
+//! Node       { "kind": "non_terminal", "name": "Declaration", "span": Span, "children": [Node] }
+//!          | { "kind": "token", "type": "T_Id", "literal": string, "span": Span }
+//!          | { "kind": "error", "span": Span, "children": [Node] }
+//! Program    { "items": [Function | Var] }
+//! Function   { "kind": "function", "name": string, "type": "int",
+//!              "params": [{ "name": string, "type": "int", "span": Span }], "body": Block,
+//!              "span": Span }
+//! Var        { "kind": "var", "name": string, "type": "int", "dims": [Expr], "init": Expr | null,
+//!              "span": Span }
+//! Block      { "stmts": [Stmt], "span": Span }
+//! Stmt       Var
+//!          | { "kind": "assign", "target": Expr, "value": Expr, "span": Span }
+//!          | { "kind": "expr", "expr": Expr, "span": Span }
+//!          | { "kind": "if", "cond": Expr, "then": Block, "else": Stmt | Block | null, "span": Span }
+//!          | { "kind": "for", "init": [Stmt], "cond": Expr | null, "update": Stmt | null,
+//!              "body": Block, "span": Span }
+//!          | { "kind": "return", "value": Expr, "span": Span }
+//!          | { "kind": "print", "args": [Expr], "span": Span }
+//!          | { "kind": "break" | "continue", "span": Span }
+//! Expr       { "kind": "int" | "bool" | "char" | "string", "value": int | bool | string, "span": Span }
+//!          | { "kind": "var", "name": string, "span": Span }
+//!          | { "kind": "index", "array": Expr, "index": Expr, "span": Span }
+//!          | { "kind": "call", "callee": string, "args": [Expr], "span": Span }
+//!          | { "kind": "unary", "op": "!", "operand": Expr, "span": Span }
+//!          | { "kind": "binary", "op": "+", "lhs": Expr, "rhs": Expr, "span": Span }
+//!          | { "kind": "error", "span": Span }
+//! Symbol     { "name": string, "kind": "var" | "param" | "func" | "array", "scope": int,
+//!              "type": "int" | "char[2][3]" | "bool(int, char)", "span": Span, "uses": int,
+//!              "writes": int }
+//! Stage      { "stage": "lowered" | "fold" | "simplify-cfg" | "dce" | "ssa" | "gvn" | "hoist"
+//!              | "copy-prop" | "out-of-ssa", "ir": string }
+//! Stats      { "functions": [{ "name": string, "lowered": int, "optimized": int }],
+//!              "lowered": int, "optimized": int }
+//! Run        { "output": string, "exit_code": int | null }
+//! Grammar    { "start": string, "nullable": [string], "first": { "Type": ["T_Int"] },
+//!              "follow": { "Type": ["T_Id"] },
+//!              "predict": { "Type": { "T_Int": ["Type -> T_Int"] } } }
+//! Diagnostic { "severity": "error" | "warning" | "note", "code": "E0201", "message": string,
+//!              "span": Span | null, "labels": [{ "span": Span, "message": string }],
+//!              "notes": [string] }
+//! ```
+//!
+//! `start` and `end` are byte offsets, `line` and `column` are 1-based.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Write},
+};
+
+use slab_tree::{NodeRef, Tree};
+
+use crate::{
+    ast::{Block, Else, Expr, Item, Literal, Program, Stmt, VarDecl},
+    diagnostic::{Diagnostic, Diagnostics, Label},
+    ir::opt::Stats,
+    semantic::symbols::{Symbol, SymbolTable},
+    syntax::SymbolTree,
+    token::{Span, Token},
+    AnalyzedGrammar,
+};
+
+/// The schema version written in the `version` field.
+pub const VERSION: i64 = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(i64),
+    String(String),
+    Array(Vec<Json>),
+    /// Keys are written in the order given.
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn object<K: Into<String>>(fields: impl IntoIterator<Item = (K, Json)>) -> Json {
+        Json::Object(fields.into_iter().map(|(k, v)| (k.into(), v)).collect())
+    }
+
+    pub fn array<T: ToJson>(items: impl IntoIterator<Item = T>) -> Json {
+        Json::Array(items.into_iter().map(|item| item.to_json()).collect())
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(b) => write!(f, "{b}"),
+            Json::Number(n) => write!(f, "{n}"),
+            Json::String(s) => write_string(f, s),
+            Json::Array(items) => {
+                f.write_char('[')?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                f.write_char(']')
+            }
+            Json::Object(fields) => {
+                f.write_char('{')?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for ch in s.chars() {
+        match ch {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            ch if ch.is_control() => write!(f, "\\u{:04x}", ch as u32)?,
+            ch => f.write_char(ch)?,
+        }
+    }
+    f.write_char('"')
+}
+
+pub trait ToJson {
+    fn to_json(&self) -> Json;
+}
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+    fn to_json(&self) -> Json {
+        (**self).to_json()
+    }
+}
+
+impl ToJson for str {
+    fn to_json(&self) -> Json {
+        Json::String(self.to_owned())
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> Json {
+        Json::String(self.clone())
+    }
+}
+
+impl ToJson for i32 {
+    fn to_json(&self) -> Json {
+        Json::Number(i64::from(*self))
+    }
+}
+
+impl ToJson for usize {
+    fn to_json(&self) -> Json {
+        Json::Number(*self as i64)
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Json {
+        self.as_ref().map_or(Json::Null, ToJson::to_json)
+    }
+}
+
+impl ToJson for Span {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("start", self.start.to_json()),
+            ("end", self.end.to_json()),
+            ("line", self.line.to_json()),
+            ("column", self.column.to_json()),
+        ])
+    }
+}
+
+impl ToJson for Token {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("type", self.token.as_ref().to_json()),
+            ("literal", self.literal.to_json()),
+            ("span", self.span.to_json()),
+        ])
+    }
+}
+
+impl ToJson for NodeRef<'_, SymbolTree> {
+    fn to_json(&self) -> Json {
+        match self.data() {
+            SymbolTree::NonTerminal(non_terminal, span) => Json::object([
+                ("kind", "non_terminal".to_json()),
+                ("name", non_terminal.as_ref().to_json()),
+                ("span", span.to_json()),
+                ("children", Json::array(self.children())),
+            ]),
+            SymbolTree::Token(token) => Json::object([
+                ("kind", "token".to_json()),
+                ("type", token.token.as_ref().to_json()),
+                ("literal", token.literal.to_json()),
+                ("span", token.span.to_json()),
+            ]),
+            SymbolTree::Error(span) => Json::object([
+                ("kind", "error".to_json()),
+                ("span", span.to_json()),
+                ("children", Json::array(self.children())),
+            ]),
+        }
+    }
+}
+
+impl ToJson for Tree<SymbolTree> {
+    fn to_json(&self) -> Json {
+        self.root().map_or(Json::Null, |root| root.to_json())
+    }
+}
+
+impl ToJson for Program {
+    fn to_json(&self) -> Json {
+        Json::object([("items", Json::array(&self.items))])
+    }
+}
+
+impl ToJson for Item {
+    fn to_json(&self) -> Json {
+        match self {
+            Item::Function(function) => Json::object([
+                ("kind", "function".to_json()),
+                ("name", function.name.name.to_json()),
+                ("type", function.return_type.to_string().to_json()),
+                (
+                    "params",
+                    Json::Array(
+                        function
+                            .params
+                            .iter()
+                            .map(|param| {
+                                Json::object([
+                                    ("name", param.name.name.to_json()),
+                                    ("type", param.ty.to_string().to_json()),
+                                    ("span", param.span.to_json()),
+                                ])
+                            })
+                            .collect(),
+                    ),
+                ),
+                ("body", function.body.to_json()),
+                ("span", function.span.to_json()),
+            ]),
+            Item::Var(var) => var.to_json(),
+        }
+    }
+}
+
+impl ToJson for VarDecl {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("kind", "var".to_json()),
+            ("name", self.name.name.to_json()),
+            ("type", self.ty.to_string().to_json()),
+            ("dims", Json::array(&self.dims)),
+            ("init", self.init.to_json()),
+            ("span", self.span.to_json()),
+        ])
+    }
+}
+
+impl ToJson for Block {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("stmts", Json::array(&self.stmts)),
+            ("span", self.span.to_json()),
+        ])
+    }
+}
+
+impl ToJson for Stmt {
+    fn to_json(&self) -> Json {
+        let (kind, mut fields) = match self {
+            Stmt::Var(var) => return var.to_json(),
+            Stmt::Assign { target, value, .. } => (
+                "assign",
+                vec![("target", target.to_json()), ("value", value.to_json())],
+            ),
+            Stmt::ExprStmt { expr, .. } => ("expr", vec![("expr", expr.to_json())]),
+            Stmt::If {
+                cond,
+                then,
+                otherwise,
+                ..
+            } => (
+                "if",
+                vec![
+                    ("cond", cond.to_json()),
+                    ("then", then.to_json()),
+                    (
+                        "else",
+                        match otherwise {
+                            Some(Else::If(stmt)) => stmt.to_json(),
+                            Some(Else::Block(block)) => block.to_json(),
+                            None => Json::Null,
+                        },
+                    ),
+                ],
+            ),
+            Stmt::For {
+                init,
+                cond,
+                update,
+                body,
+                ..
+            } => (
+                "for",
+                vec![
+                    ("init", Json::array(init)),
+                    ("cond", cond.to_json()),
+                    ("update", update.as_deref().to_json()),
+                    ("body", body.to_json()),
+                ],
+            ),
+            Stmt::Return { value, .. } => ("return", vec![("value", value.to_json())]),
+            Stmt::Print { args, .. } => ("print", vec![("args", Json::array(args))]),
+            Stmt::Break(_) => ("break", vec![]),
+            Stmt::Continue(_) => ("continue", vec![]),
+        };
+        fields.insert(0, ("kind", kind.to_json()));
+        fields.push(("span", self.span().to_json()));
+        Json::object(fields)
+    }
+}
+
+impl ToJson for Expr {
+    fn to_json(&self) -> Json {
+        let (kind, mut fields) = match self {
+            Expr::Literal(literal, _) => match literal {
+                Literal::Int(value) => ("int", vec![("value", Json::Number((*value).into()))]),
+                Literal::Bool(value) => ("bool", vec![("value", Json::Bool(*value))]),
+                Literal::Char(value) => ("char", vec![("value", value.to_string().to_json())]),
+                Literal::String(value) => ("string", vec![("value", value.to_json())]),
+            },
+            Expr::Var(ident) => ("var", vec![("name", ident.name.to_json())]),
+            Expr::Index { array, index, .. } => (
+                "index",
+                vec![("array", array.to_json()), ("index", index.to_json())],
+            ),
+            Expr::Call { callee, args, .. } => (
+                "call",
+                vec![
+                    ("callee", callee.name.to_json()),
+                    ("args", Json::array(args)),
+                ],
+            ),
+            Expr::Unary { op, operand, .. } => (
+                "unary",
+                vec![
+                    ("op", op.to_string().to_json()),
+                    ("operand", operand.to_json()),
+                ],
+            ),
+            Expr::Binary { op, lhs, rhs, .. } => (
+                "binary",
+                vec![
+                    ("op", op.as_str().to_json()),
+                    ("lhs", lhs.to_json()),
+                    ("rhs", rhs.to_json()),
+                ],
+            ),
+            Expr::Error(_) => ("error", vec![]),
+        };
+        fields.insert(0, ("kind", kind.to_json()));
+        fields.push(("span", self.span().to_json()));
+        Json::object(fields)
+    }
+}
+
+impl ToJson for Symbol {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("name", self.name.to_json()),
+            ("kind", self.kind.to_string().to_json()),
+            ("scope", self.scope.0.to_json()),
+            ("type", self.ty.to_string().to_json()),
+            ("span", self.span.to_json()),
+            ("uses", self.uses.to_json()),
+            ("writes", self.writes.to_json()),
+        ])
+    }
+}
+
+impl ToJson for SymbolTable {
+    fn to_json(&self) -> Json {
+        Json::array(self)
+    }
+}
+
+impl ToJson for AnalyzedGrammar {
+    fn to_json(&self) -> Json {
+        let (Some(grammar), Some(analysis)) = (&self.grammar, &self.analysis) else {
+            return Json::Null;
+        };
+        let sets = |sets: &BTreeMap<String, BTreeSet<String>>| {
+            Json::object(
+                grammar
+                    .non_terminals
+                    .iter()
+                    .map(|non_terminal| (non_terminal.as_str(), Json::array(&sets[non_terminal]))),
+            )
+        };
+
+        let mut predict: Vec<(String, Json)> = vec![];
+        for ((non_terminal, terminal), productions) in grammar.predict_table(analysis) {
+            let productions = Json::array(
+                productions
+                    .iter()
+                    .map(|&index| grammar.productions[index].to_string()),
+            );
+            match predict.iter_mut().find(|(name, _)| *name == non_terminal) {
+                Some((_, Json::Object(cells))) => cells.push((terminal, productions)),
+                _ => predict.push((non_terminal, Json::Object(vec![(terminal, productions)]))),
+            }
+        }
+
+        Json::object([
+            ("start", grammar.start().to_json()),
+            (
+                "nullable",
+                Json::array(
+                    grammar
+                        .non_terminals
+                        .iter()
+                        .filter(|non_terminal| analysis.nullable.contains(*non_terminal)),
+                ),
+            ),
+            ("first", sets(&analysis.first)),
+            ("follow", sets(&analysis.follow)),
+            ("predict", Json::Object(predict)),
+        ])
+    }
+}
+
+impl ToJson for Stats {
+    fn to_json(&self) -> Json {
+        let (lowered, optimized) = self.total();
+        Json::object([
+            (
+                "functions",
+                Json::Array(
+                    self.functions
+                        .iter()
+                        .map(|function| {
+                            Json::object([
+                                ("name", function.name.to_json()),
+                                ("lowered", function.lowered.to_json()),
+                                ("optimized", function.optimized.to_json()),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+            ("lowered", lowered.to_json()),
+            ("optimized", optimized.to_json()),
+        ])
+    }
+}
+
+impl ToJson for Label {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("span", self.span.to_json()),
+            ("message", self.message.to_json()),
+        ])
+    }
+}
+
+impl ToJson for Diagnostic {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("severity", self.severity.to_string().to_json()),
+            ("code", self.code.to_json()),
+            ("message", self.message.to_json()),
+            ("span", self.span.to_json()),
+            ("labels", Json::array(&self.labels)),
+            ("notes", Json::array(&self.notes)),
+        ])
+    }
+}
+
+impl ToJson for Diagnostics {
+    fn to_json(&self) -> Json {
+        Json::array(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_strings_and_keeps_key_order() {
+        let json = Json::object([
+            ("b", Json::String("a \"quote\"\n\\ \u{1}".to_owned())),
+            (
+                "a",
+                Json::Array(vec![Json::Null, Json::Bool(true), Json::Number(-3)]),
+            ),
+        ]);
+        assert_eq!(
+            json.to_string(),
+            r#"{"b":"a \"quote\"\n\\ \u0001","a":[null,true,-3]}"#
+        );
+    }
+
+    #[test]
+    fn tokens_and_tree_follow_the_schema() {
+        let tokens = crate::tokenize("x");
+        assert_eq!(
+            tokens[0].to_json().to_string(),
+            r#"{"type":"T_Id","literal":"x","span":{"start":0,"end":1,"line":1,"column":1}}"#
+        );
+
+        let tree = crate::parse("").tree.unwrap();
+        assert_eq!(
+            tree.to_json().to_string(),
+            concat!(
+                r#"{"kind":"non_terminal","name":"Program","span":{"start":0,"end":0,"line":1,"column":1},"children":["#,
+                r#"{"kind":"non_terminal","name":"Declarations","span":{"start":0,"end":0,"line":1,"column":1},"children":[]}]}"#
+            )
+        );
+    }
+
+    #[test]
+    fn ast_follows_the_schema() {
+        let program = crate::lower("int x = !a[1];").program.unwrap();
+        let span = |start, end| {
+            format!(
+                r#"{{"start":{start},"end":{end},"line":1,"column":{}}}"#,
+                start + 1
+            )
+        };
+        assert_eq!(
+            program.to_json().to_string(),
+            format!(
+                concat!(
+                    r#"{{"items":[{{"kind":"var","name":"x","type":"int","dims":[],"init":"#,
+                    r#"{{"kind":"unary","op":"!","operand":{{"kind":"index","array":{{"kind":"var","name":"a","span":{}}},"#,
+                    r#""index":{{"kind":"int","value":1,"span":{}}},"span":{}}},"span":{}}},"span":{}}}]}}"#
+                ),
+                span(9, 10),
+                span(11, 12),
+                span(9, 13),
+                span(8, 13),
+                span(4, 13)
+            )
+        );
+    }
+}
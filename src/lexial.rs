@@ -1,6 +1,11 @@
-use crate::token::{Token, TokenType};
+use crate::diagnostic::{code, Diagnostic, Diagnostics};
+use crate::token::{Span, Token, TokenType};
 
-pub(crate) struct Lexer {
+/// Turns source text into [`Token`]s.
+///
+/// With `take_whitespace` set, spaces and newlines are returned as
+/// [`TokenType::T_Whitespace`] tokens instead of being skipped.
+pub struct Lexer {
     input: String,
     position: usize,
     read_position: usize,
@@ -26,15 +31,6 @@ impl Lexer {
     }
 
     pub fn next_token(&mut self) -> Token {
-        if self.peek_char() == '\0' {
-            return Token {
-                token: TokenType::End,
-                literal: "End".to_owned(),
-                line: self.line,
-                column: self.column,
-            };
-        }
-
         if !self.take_whitespace {
             while self.ch == '\r' || self.ch == '\n' || self.ch == '\t' || self.ch == ' ' {
                 self.read_char()
@@ -44,12 +40,20 @@ impl Lexer {
                 self.read_char()
             }
         } else if self.ch == ' ' || self.ch == '\n' {
+            let start = self.start();
             self.read_char();
             return Token {
                 token: TokenType::T_Whitespace,
                 literal: "whitespace".to_owned(),
-                line: self.line,
-                column: self.column,
+                span: self.span_from(start),
+            };
+        }
+        let start = self.start();
+        if self.is_end() {
+            return Token {
+                token: TokenType::End,
+                literal: "End".to_owned(),
+                span: self.span_from(start),
             };
         }
         let mut skip = false;
@@ -154,7 +158,7 @@ impl Lexer {
                 self.read_char();
                 (
                     TokenType::T_Character,
-                    format!("'{}", self.input[pos..self.position + 1].to_string()),
+                    format!("'{}", &self.input[pos..self.position + 1]),
                 )
             }
             '0' => {
@@ -177,8 +181,8 @@ impl Lexer {
             }
             '\0' => (TokenType::End, "".to_owned()),
             _ if self.is_letter(self.ch) => {
+                skip = true;
                 let ide = self.read_identifier();
-                self.read_position -= 1;
                 if self.lookup_ident(&ide) != TokenType::ILLEGAL {
                     (self.lookup_ident(&ide), ide)
                 } else {
@@ -196,13 +200,30 @@ impl Lexer {
         Token {
             token: token_type,
             literal,
+            span: self.span_from(start),
+        }
+    }
+
+    /// A zero width span at the current character.
+    fn start(&self) -> Span {
+        Span {
+            start: self.position,
+            end: self.position,
             line: self.line,
             column: self.column,
         }
     }
 
+    /// Extends `start` up to the current character.
+    fn span_from(&self, start: Span) -> Span {
+        Span {
+            end: self.position,
+            ..start
+        }
+    }
+
     fn is_letter(&self, ch: char) -> bool {
-        (ch >= 'a' && ch <= 'z') || (ch >= 'A' && ch <= 'Z') || ch == '_'
+        ch.is_ascii_alphabetic() || ch == '_'
     }
 
     pub fn is_end(&self) -> bool {
@@ -210,17 +231,13 @@ impl Lexer {
     }
 
     fn is_digit(&self, ch: char) -> bool {
-        (ch >= '0' && ch <= '9') || ch == '.'
+        ch.is_ascii_digit() || ch == '.'
     }
 
     fn read_char(&mut self) {
-        if self.read_position >= self.input.len() {
-            self.ch = '\0';
-        } else {
-            self.ch = self.input.chars().nth(self.read_position).unwrap();
-        }
-        self.position = self.read_position;
-        self.read_position += 1;
+        self.position = self.read_position.min(self.input.len());
+        self.ch = self.input[self.position..].chars().next().unwrap_or('\0');
+        self.read_position = self.position + self.ch.len_utf8();
         if self.ch == '\n' {
             self.line += 1;
             self.column = 0;
@@ -230,11 +247,10 @@ impl Lexer {
     }
 
     fn peek_char(&self) -> char {
-        if self.read_position >= self.input.len() {
-            '\0'
-        } else {
-            self.input.chars().nth(self.read_position).unwrap()
-        }
+        self.input
+            .get(self.read_position..)
+            .and_then(|rest| rest.chars().next())
+            .unwrap_or('\0')
     }
 
     fn read_digit(&mut self) -> String {
@@ -302,14 +318,34 @@ impl Lexer {
         }
     }
 
+    #[allow(clippy::wrong_self_convention)]
     pub fn to_vec(&mut self) -> Vec<Token> {
         let mut tokens = Vec::with_capacity(100);
-        while !self.is_end() {
+        loop {
             let token = self.next_token();
             if token.token == TokenType::T_Comment {
                 continue;
             }
+            let is_end = token.token == TokenType::End;
             tokens.push(token);
+            if is_end {
+                break;
+            }
+        }
+        tokens
+    }
+
+    /// Like [`Lexer::to_vec`], but also reports every illegal character.
+    pub fn tokenize(&mut self, diagnostics: &mut Diagnostics) -> Vec<Token> {
+        let tokens = self.to_vec();
+        for token in tokens.iter().filter(|t| t.token == TokenType::ILLEGAL) {
+            diagnostics.push(
+                Diagnostic::error(
+                    code::ILLEGAL_CHARACTER,
+                    format!("illegal character `{}`", token.literal),
+                )
+                .at(token),
+            );
         }
         tokens
     }
@@ -336,14 +372,46 @@ mod tests {
     const TEST_OUT: &str = include_str!("./../tests/test.out");
     const TEST_OUT_WH: &str = include_str!("./../tests/test_wh.out");
 
+    #[test]
+    fn tokens_carry_start_spans() {
+        let source = "int x;\n  // é\n\tfoo(\"a\nb\", 'c');";
+        let tokens = super::Lexer::new(source.to_owned(), false).to_vec();
+        let spans: Vec<_> = tokens
+            .iter()
+            .map(|t| {
+                (
+                    &source[t.span.start..t.span.end],
+                    t.span.line,
+                    t.span.column,
+                )
+            })
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                ("int", 1, 1),
+                ("x", 1, 5),
+                (";", 1, 6),
+                ("foo", 3, 2),
+                ("(", 3, 5),
+                ("\"a\nb\"", 3, 6),
+                (",", 4, 3),
+                ("'c'", 4, 5),
+                (")", 4, 8),
+                (";", 4, 9),
+                ("", 4, 10),
+            ]
+        );
+    }
+
     #[test]
     fn test_via_c_file() {
         let whitespace = false;
         let mut lexer = super::Lexer::new(TEST_IN.replace("\r", "").to_string(), whitespace);
         let out_put = if whitespace {
-            TEST_OUT_WH.split("\n").into_iter().collect::<Vec<&str>>()
+            TEST_OUT_WH.split("\n").collect::<Vec<&str>>()
         } else {
-            TEST_OUT.split("\n").into_iter().collect::<Vec<&str>>()
+            TEST_OUT.split("\n").collect::<Vec<&str>>()
         };
         let mut i = 0;
         // let mut cols = 0;
@@ -358,7 +426,7 @@ mod tests {
                 "token is {} -> {} line {} , but excpected {:?}",
                 token.literal,
                 token.token.as_ref(),
-                token.line,
+                token.span.line,
                 out_put[i]
             );
             i += 1;
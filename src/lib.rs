@@ -0,0 +1,467 @@
+//! A compiler for a small C-like language with `int`, `bool` and `char`
+//! variables, arrays, functions, `if`/`else if`/`else`, `for` and `print`.
+//!
+//! The phases can be driven one by one through [`lexial::Lexer`],
+//! [`syntax::parser::Parser`], [`ast::lower`], [`semantic::Sem`],
+//! [`ir::lower`], [`ir::opt::optimize`] and [`interp::run`], or all at once
+//! through [`tokenize`], [`parse`], [`lower`], [`check`], [`compile`] and
+//! [`run`].
+//!
+//! ```
+//! let result = compiler::check("int main() { return 0; }");
+//! assert!(!result.diagnostics.has_errors());
+//! assert_eq!(result.symbols.iter().next().unwrap().name, "main");
+//! ```
+
+pub mod ast;
+pub mod diagnostic;
+pub mod interp;
+pub mod ir;
+pub mod json;
+pub mod lexial;
+pub mod semantic;
+pub mod syntax;
+pub mod token;
+
+pub use slab_tree;
+
+use std::io::Write;
+
+use slab_tree::Tree;
+
+use ast::Program;
+use diagnostic::{code, Diagnostic, Diagnostics, Lints};
+use ir::opt::{OptLevel, Pass};
+use lexial::Lexer;
+use semantic::{symbols::SymbolTable, ExprTypes, LoopTargets, Sem};
+use syntax::{
+    grammar::{Analysis, ConflictKind, Grammar, END},
+    parser::Parser,
+    SymbolTree,
+};
+use token::{Span, Token, TokenType};
+
+/// The output of [`lex`].
+pub struct Lexed {
+    pub tokens: Vec<Token>,
+    pub diagnostics: Diagnostics,
+}
+
+/// The output of [`parse`].
+pub struct Parsed {
+    /// The parse tree, with [`SymbolTree::Error`] nodes where syntax errors
+    /// were recovered from. `None` only when the parser could not recover.
+    pub tree: Option<Tree<SymbolTree>>,
+    /// Lexical and syntax errors.
+    pub diagnostics: Diagnostics,
+}
+
+/// The output of [`lower`].
+pub struct Lowered {
+    /// The AST, or `None` when the parser could not recover.
+    pub program: Option<Program>,
+    /// Lexical and syntax errors followed by the errors found while
+    /// lowering.
+    pub diagnostics: Diagnostics,
+}
+
+/// The output of [`check`].
+pub struct Checked {
+    /// The AST, or `None` when the parser could not recover.
+    pub program: Option<Program>,
+    /// The scopes of the program and every declared variable, parameter and
+    /// function.
+    pub symbols: SymbolTable,
+    /// The type of every expression, by its span.
+    pub types: ExprTypes,
+    /// The loop of every `break` and `continue` that is inside one.
+    pub loop_targets: LoopTargets,
+    /// Lexical and syntax errors followed by semantic errors.
+    pub diagnostics: Diagnostics,
+}
+
+/// The output of [`compile`].
+pub struct Compiled {
+    /// The IR, or `None` when the program has errors.
+    pub module: Option<ir::Module>,
+    /// Everything [`check`] reports.
+    pub diagnostics: Diagnostics,
+}
+
+/// The output of [`run`].
+pub struct Ran {
+    /// What `main` returned, or `None` when the program has errors or
+    /// stopped with a runtime error.
+    pub exit_code: Option<i32>,
+    /// Everything [`check`] reports, followed by the runtime error.
+    pub diagnostics: Diagnostics,
+}
+
+/// The output of [`analyze_grammar`].
+pub struct AnalyzedGrammar {
+    /// The grammar, or `None` when it has syntax errors.
+    pub grammar: Option<Grammar>,
+    pub analysis: Option<Analysis>,
+    /// Syntax errors, LL(1) conflicts, left recursion, unknown terminals and
+    /// unproductive or unreachable non-terminals.
+    pub diagnostics: Diagnostics,
+}
+
+/// Splits `source` into tokens, skipping comments. The last token is always
+/// [`TokenType::End`].
+pub fn tokenize(source: &str) -> Vec<Token> {
+    Lexer::new(source.to_owned(), false).to_vec()
+}
+
+/// Like [`tokenize`], but also reports illegal characters.
+pub fn lex(source: &str) -> Lexed {
+    let mut diagnostics = Diagnostics::new();
+    let tokens = Lexer::new(source.to_owned(), false).tokenize(&mut diagnostics);
+    Lexed {
+        tokens,
+        diagnostics,
+    }
+}
+
+/// Parses `source` into a parse tree rooted at `Program`.
+pub fn parse(source: &str) -> Parsed {
+    let Lexed {
+        tokens,
+        mut diagnostics,
+    } = lex(source);
+    let tree = Parser::from_tokens(tokens).parse(&mut diagnostics);
+    Parsed { tree, diagnostics }
+}
+
+/// Parses `source` and lowers the parse tree into an AST.
+pub fn lower(source: &str) -> Lowered {
+    let Parsed {
+        tree,
+        mut diagnostics,
+    } = parse(source);
+    let program = tree.map(|tree| ast::lower(&tree, &mut diagnostics));
+    Lowered {
+        program,
+        diagnostics,
+    }
+}
+
+/// Lowers `source` and runs the semantic checks on the AST, also on the
+/// parts that survived syntax errors. The checks are skipped when the parser
+/// could not produce a tree.
+pub fn check(source: &str) -> Checked {
+    check_with(source, Lints::default())
+}
+
+/// Like [`check`], with the [`Lint`](diagnostic::Lint)s at the levels in
+/// `lints` instead of all warning.
+pub fn check_with(source: &str, lints: Lints) -> Checked {
+    let Lowered {
+        program,
+        mut diagnostics,
+    } = lower(source);
+    let Some(program) = program else {
+        return Checked {
+            program: None,
+            symbols: SymbolTable::new(),
+            types: ExprTypes::new(),
+            loop_targets: LoopTargets::new(),
+            diagnostics,
+        };
+    };
+
+    let mut sem = Sem::with_lints(lints);
+    sem.check(&program, &mut diagnostics);
+    let (symbols, types, loop_targets) = sem.finish();
+
+    Checked {
+        program: Some(program),
+        symbols,
+        types,
+        loop_targets,
+        diagnostics,
+    }
+}
+
+/// Checks `source` with the lints at the levels in `lints` and, when it has
+/// no errors, lowers it into the three-address IR and optimizes it at
+/// [`OptLevel::O1`].
+pub fn compile(source: &str, lints: Lints) -> Compiled {
+    compile_with(source, lints, OptLevel::default(), |_, _| {})
+}
+
+/// Like [`compile`], optimizing at `level`. `dump` is called with the IR as
+/// it is lowered, with `None`, and after every pass that changes it.
+pub fn compile_with(
+    source: &str,
+    lints: Lints,
+    level: OptLevel,
+    mut dump: impl FnMut(Option<Pass>, &ir::Module),
+) -> Compiled {
+    let Checked {
+        program,
+        symbols,
+        types,
+        diagnostics,
+        ..
+    } = check_with(source, lints);
+    let module = match program {
+        Some(program) if !diagnostics.has_errors() => {
+            let mut module = ir::lower(&program, &symbols, &types);
+            dump(None, &module);
+            ir::opt::optimize(&mut module, level, |pass, module| dump(Some(pass), module));
+            Some(module)
+        }
+        _ => None,
+    };
+    Compiled {
+        module,
+        diagnostics,
+    }
+}
+
+/// Checks `source` with the lints at the levels in `lints` and, when it has
+/// no errors, runs it from `main`, writing what it prints to `out`.
+pub fn run(source: &str, lints: Lints, out: &mut (dyn Write + Send)) -> Ran {
+    let Checked {
+        program,
+        symbols,
+        mut diagnostics,
+        ..
+    } = check_with(source, lints);
+    let exit_code = match program {
+        Some(program) if !diagnostics.has_errors() => match interp::run(&program, &symbols, out) {
+            Ok(exit_code) => Some(exit_code),
+            Err(error) => {
+                diagnostics.push(*error);
+                None
+            }
+        },
+        _ => None,
+    };
+    Ran {
+        exit_code,
+        diagnostics,
+    }
+}
+
+/// Reads a grammar in the `grammer.g` notation, computes its FIRST and
+/// FOLLOW sets and reports everything that keeps it from being LL(1).
+pub fn analyze_grammar(text: &str) -> AnalyzedGrammar {
+    let mut diagnostics = Diagnostics::new();
+    let grammar = match Grammar::parse(text) {
+        Ok(grammar) => grammar,
+        Err(err) => {
+            diagnostics.push(
+                Diagnostic::error(code::GRAMMAR_SYNTAX, err.message)
+                    .with_span(line_span(text, err.line)),
+            );
+            return AnalyzedGrammar {
+                grammar: None,
+                analysis: None,
+                diagnostics,
+            };
+        }
+    };
+    let analysis = grammar.analyze();
+    let span_of = |index: usize| line_span(text, grammar.productions[index].line);
+    let first_production =
+        |non_terminal: &str| grammar.productions_of(non_terminal).next().unwrap().0;
+
+    for terminal in grammar.terminals() {
+        if terminal != END && terminal.parse::<TokenType>().is_err() {
+            let (index, _) = grammar
+                .productions
+                .iter()
+                .enumerate()
+                .find(|(_, production)| production.rhs.iter().any(|symbol| symbol == terminal))
+                .unwrap();
+            diagnostics.push(
+                Diagnostic::error(
+                    code::UNKNOWN_TERMINAL,
+                    format!("`{terminal}` is neither a non-terminal nor a token type"),
+                )
+                .with_span(span_of(index)),
+            );
+        }
+    }
+
+    for non_terminal in grammar.unproductive() {
+        diagnostics.push(
+            Diagnostic::error(
+                code::UNPRODUCTIVE,
+                format!("`{non_terminal}` never derives a string of terminals"),
+            )
+            .with_span(span_of(first_production(non_terminal))),
+        );
+    }
+
+    for non_terminal in grammar.unreachable() {
+        diagnostics.push(
+            Diagnostic::warning(
+                code::UNREACHABLE,
+                format!("`{non_terminal}` is unreachable from `{}`", grammar.start()),
+            )
+            .with_span(span_of(first_production(non_terminal))),
+        );
+    }
+
+    for cycle in grammar.left_recursion(&analysis) {
+        let start = &grammar.productions[cycle[0]].lhs;
+        let path: Vec<&str> = cycle
+            .iter()
+            .map(|&index| grammar.productions[index].lhs.as_str())
+            .chain([start.as_str()])
+            .collect();
+        let mut error = Diagnostic::error(
+            code::LEFT_RECURSION,
+            format!("`{start}` is left recursive: {}", path.join(" => ")),
+        )
+        .with_span(span_of(cycle[0]));
+        for &index in &cycle[1..] {
+            error = error.with_label(span_of(index), "continues here");
+        }
+        diagnostics.push(error);
+    }
+
+    for conflict in grammar.conflicts(&analysis) {
+        let mut error = Diagnostic::error(
+            code::LL1_CONFLICT,
+            format!(
+                "{} conflict: `{}` has {} productions for `{}`",
+                conflict.kind,
+                conflict.non_terminal,
+                conflict.productions.len(),
+                conflict.terminal
+            ),
+        )
+        .with_span(span_of(conflict.productions[0]));
+        for &index in &conflict.productions[1..] {
+            error = error.with_label(span_of(index), "also predicted here");
+        }
+        if conflict.kind == ConflictKind::FirstFollow {
+            error = error.with_note(format!(
+                "`{}` is in FOLLOW({}) and {} can derive ''",
+                conflict.terminal, conflict.non_terminal, conflict.non_terminal
+            ));
+        }
+        diagnostics.push(error);
+    }
+
+    AnalyzedGrammar {
+        grammar: Some(grammar),
+        analysis: Some(analysis),
+        diagnostics,
+    }
+}
+
+/// The span of the 1-based `line` of `text`, without its line ending.
+fn line_span(text: &str, line: usize) -> Span {
+    let start: usize = text
+        .split_inclusive('\n')
+        .take(line - 1)
+        .map(str::len)
+        .sum();
+    let rest = &text[start.min(text.len())..];
+    let len = rest.lines().next().unwrap_or("").len();
+    Span {
+        start,
+        end: start + len,
+        line,
+        column: 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenize_ends_with_end() {
+        let tokens = tokenize("int x;");
+        let types: Vec<_> = tokens.iter().map(|t| t.token.clone()).collect();
+        assert_eq!(
+            types,
+            [
+                TokenType::T_Int,
+                TokenType::T_Id,
+                TokenType::T_Semicolon,
+                TokenType::End
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_and_parse_report_error_codes() {
+        use crate::diagnostic::code;
+
+        assert_eq!(
+            lex("int @x;").diagnostics.codes(),
+            [code::ILLEGAL_CHARACTER]
+        );
+
+        let parsed = parse("int main() { int x = 1 }");
+        assert!(parsed.diagnostics.has_errors());
+        assert_eq!(parsed.diagnostics.codes()[0], code::UNEXPECTED_TOKEN);
+        let span = parsed.diagnostics.iter().next().unwrap().span.unwrap();
+        assert_eq!((span.line, span.column, span.len()), (1, 24, 1));
+    }
+
+    #[test]
+    fn check_reports_symbols_and_errors() {
+        let ok = check("int main() {\n  int x = 1;\n  return x;\n}");
+        assert!(ok.diagnostics.is_empty(), "{:?}", ok.diagnostics);
+        let names: Vec<_> = ok
+            .symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.scope.0))
+            .collect();
+        assert_eq!(names, [("main", 0), ("x", 1)]);
+
+        let bad = check("int main() { int x = 1; int x = 2; return x; }");
+        assert_eq!(
+            bad.diagnostics.codes(),
+            [diagnostic::code::DUPLICATE_DECLARATION]
+        );
+        assert!(bad.program.is_some());
+    }
+
+    #[test]
+    fn analyze_grammar_reports_problems_at_their_lines() {
+        let ok = analyze_grammar(syntax::GRAMMAR);
+        assert!(ok.diagnostics.is_empty(), "{:?}", ok.diagnostics);
+
+        let bad = analyze_grammar("S -> T_Id A\nA -> T_Id\nA -> T_Id T_Semicolon\nB -> Oops\n");
+        assert_eq!(
+            bad.diagnostics.codes(),
+            [
+                code::UNKNOWN_TERMINAL,
+                code::UNREACHABLE,
+                code::LL1_CONFLICT
+            ]
+        );
+        let conflict = bad.diagnostics.iter().last().unwrap();
+        assert_eq!(conflict.span.unwrap().line, 2);
+        assert_eq!(conflict.labels[0].span.line, 3);
+        assert_eq!(
+            (conflict.labels[0].span.start, conflict.labels[0].span.end),
+            (22, 43)
+        );
+
+        let syntax = analyze_grammar("S -> a\nS b\n");
+        assert!(syntax.grammar.is_none());
+        assert_eq!(syntax.diagnostics.codes(), [code::GRAMMAR_SYNTAX]);
+    }
+
+    #[test]
+    fn check_runs_after_syntax_errors() {
+        let checked = check_with(
+            "int main() { int x = ; int y = 1; int y = 2; return 0; }",
+            Lints::allow_all(),
+        );
+        assert_eq!(
+            checked.diagnostics.codes(),
+            [code::UNEXPECTED_TOKEN, code::DUPLICATE_DECLARATION]
+        );
+    }
+}
@@ -1,13 +1,317 @@
-use semantic::Sem;
+use std::{
+    env, fs,
+    io::{self, IsTerminal, Read, Write},
+    process::ExitCode,
+};
 
-pub(crate) mod lexial;
-pub(crate) mod semantic;
-pub(crate) mod syntax;
-pub(crate) mod token;
+use cli::{Args, ColorChoice, Command, Format, Input, Options, USAGE};
+use compiler::{
+    diagnostic::{Diagnostics, Renderer},
+    ir::{
+        self,
+        cfg::{self, Cfg},
+        opt::{Pass, Stats},
+    },
+    json::{self, Json, ToJson},
+    slab_tree::NodeRef,
+    syntax::{SymbolTree, GRAMMAR},
+    token::TokenType,
+};
 
-const TEST_IN: &str = include_str!("./../tests/test.c");
+mod cli;
 
-fn main() {
-    let mut sem = Sem::new(TEST_IN).unwrap();
-    sem.parser();
+fn main() -> ExitCode {
+    let options = match Args::parse(env::args().skip(1)) {
+        Ok(Args::Run(options)) => options,
+        Ok(Args::Help) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(err) => {
+            eprintln!("error: {err}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let color = match options.color {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => io::stderr().is_terminal() && env::var_os("NO_COLOR").is_none(),
+    };
+
+    let mut failed = false;
+    let mut exit_code = None;
+    for input in &options.inputs {
+        let source = match read_input(input) {
+            Ok(source) => source,
+            Err(err) => {
+                eprintln!("error: cannot read {input}: {err}");
+                return ExitCode::from(2);
+            }
+        };
+        let file_name = input.to_string();
+        let (diagnostics, ran) = compile(&options, &source, &file_name);
+        // JSON documents carry their own diagnostics.
+        if options.format != Format::Json {
+            let renderer = Renderer::new(&source, &file_name).with_color(color);
+            for diagnostic in &diagnostics {
+                eprintln!("{}", renderer.render(diagnostic));
+            }
+        }
+        failed |= diagnostics.has_errors();
+        exit_code = ran.or(exit_code);
+    }
+
+    match exit_code {
+        _ if failed => ExitCode::FAILURE,
+        // Exit codes are a byte, like the ones of C programs.
+        Some(exit_code) => ExitCode::from(exit_code as u8),
+        None => ExitCode::SUCCESS,
+    }
+}
+
+fn read_input(input: &Input) -> io::Result<String> {
+    match input {
+        Input::Stdin => {
+            let mut source = String::new();
+            io::stdin().read_to_string(&mut source)?;
+            Ok(source)
+        }
+        Input::Path(path) => fs::read_to_string(path),
+        Input::Grammar => Ok(GRAMMAR.to_owned()),
+    }
+}
+
+/// Runs the requested command on one source file, printing its output and
+/// returning what was reported about it and, for `run`, what `main`
+/// returned.
+fn compile(options: &Options, source: &str, file_name: &str) -> (Diagnostics, Option<i32>) {
+    let mut exit_code = None;
+    // The IR after every stage, for `ir --dump-passes`.
+    let mut passes = vec![];
+    // For `--stats`.
+    let mut stats = None;
+    let (output, diagnostics) = match options.command {
+        Command::Lex => {
+            let lexed = compiler::lex(source);
+            for token in &lexed.tokens {
+                match options.format {
+                    Format::Text if token.token != TokenType::End => {
+                        println!("{} -> {}", token.literal, token.token.as_ref())
+                    }
+                    Format::Text | Format::Json => {}
+                    Format::Debug => println!("{:?}", token),
+                }
+            }
+            (("tokens", Json::array(&lexed.tokens)), lexed.diagnostics)
+        }
+        Command::Parse => {
+            let parsed = compiler::parse(source);
+            if let Some(tree) = &parsed.tree {
+                match options.format {
+                    Format::Text => print_tree(tree.root().unwrap()),
+                    Format::Debug => {
+                        let mut s = String::new();
+                        tree.write_formatted(&mut s).unwrap();
+                        print!("{}", s);
+                    }
+                    Format::Json => {}
+                }
+            }
+            // Only built for JSON output, it nests as deep as the tree.
+            let tree = match options.format {
+                Format::Json => parsed.tree.to_json(),
+                _ => Json::Null,
+            };
+            (("tree", tree), parsed.diagnostics)
+        }
+        Command::Ast => {
+            let lowered = compiler::lower(source);
+            if let Some(program) = &lowered.program {
+                match options.format {
+                    Format::Text => print!("{program}"),
+                    Format::Debug => println!("{:#?}", program),
+                    Format::Json => {}
+                }
+            }
+            (("ast", lowered.program.to_json()), lowered.diagnostics)
+        }
+        Command::Check => {
+            let checked = compiler::check_with(source, options.lints);
+            if options.format == Format::Debug {
+                for symbol in &checked.symbols {
+                    println!("{:?}", symbol);
+                }
+            }
+            (
+                ("symbols", Json::array(&checked.symbols)),
+                checked.diagnostics,
+            )
+        }
+        Command::Ir if options.dump_passes => {
+            let compiled;
+            (compiled, stats) = compile_ir(options, source, |pass, module| {
+                let stage = pass.map_or("lowered".to_owned(), |pass| pass.to_string());
+                let header = pass.map_or("lowered".to_owned(), |pass| format!("after {pass}"));
+                match options.format {
+                    Format::Text => print!("; {header}\n{module}\n"),
+                    Format::Debug => println!("; {header}\n{:#?}", module),
+                    Format::Json => passes.push(Json::object([
+                        ("stage", stage.to_json()),
+                        ("ir", module.to_string().to_json()),
+                    ])),
+                }
+            });
+            let ir = compiled.module.as_ref().map(ToString::to_string);
+            (("ir", ir.to_json()), compiled.diagnostics)
+        }
+        Command::Ir => {
+            let compiled;
+            (compiled, stats) = compile_ir(options, source, |_, _| {});
+            if let Some(module) = &compiled.module {
+                match options.format {
+                    Format::Text => print!("{module}"),
+                    Format::Debug => println!("{:#?}", module),
+                    Format::Json => {}
+                }
+            }
+            let ir = compiled.module.as_ref().map(ToString::to_string);
+            (("ir", ir.to_json()), compiled.diagnostics)
+        }
+        Command::Cfg => {
+            let compiled;
+            (compiled, stats) = compile_ir(options, source, |_, _| {});
+            if let Some(module) = &compiled.module {
+                match options.format {
+                    Format::Text => print!("{}", cfg::to_dot(module)),
+                    Format::Debug => {
+                        for function in &module.functions {
+                            println!("{:#?}", Cfg::new(function));
+                        }
+                    }
+                    Format::Json => {}
+                }
+            }
+            let dot = compiled.module.as_ref().map(cfg::to_dot);
+            (("cfg", dot.to_json()), compiled.diagnostics)
+        }
+        Command::Run => {
+            // JSON documents carry the output instead.
+            let mut output = vec![];
+            let ran = if options.format == Format::Json {
+                compiler::run(source, options.lints, &mut output)
+            } else {
+                let mut stdout = io::stdout();
+                let ran = compiler::run(source, options.lints, &mut stdout);
+                let _ = stdout.flush();
+                ran
+            };
+            exit_code = ran.exit_code;
+            (
+                (
+                    "run",
+                    Json::object([
+                        ("output", String::from_utf8_lossy(&output).to_json()),
+                        ("exit_code", ran.exit_code.to_json()),
+                    ]),
+                ),
+                ran.diagnostics,
+            )
+        }
+        Command::Grammar => {
+            let analyzed = compiler::analyze_grammar(source);
+            if let (Some(grammar), Some(analysis)) = (&analyzed.grammar, &analyzed.analysis) {
+                match options.format {
+                    Format::Text => print!("{}", grammar.report(analysis)),
+                    Format::Debug => println!("{:#?}", analysis),
+                    Format::Json => {}
+                }
+            }
+            (("grammar", analyzed.to_json()), analyzed.diagnostics)
+        }
+    };
+
+    // On stderr, so that the output of `cfg` stays a graph.
+    if let Some(stats) = &stats {
+        match options.format {
+            Format::Text => eprint!("{stats}"),
+            Format::Debug => eprintln!("{:#?}", stats),
+            Format::Json => {}
+        }
+    }
+    if options.format == Format::Json {
+        let mut fields = vec![
+            ("version", Json::Number(json::VERSION)),
+            ("file", file_name.to_json()),
+            output,
+        ];
+        if options.dump_passes && options.command == Command::Ir {
+            fields.push(("passes", Json::Array(passes)));
+        }
+        if options.stats && matches!(options.command, Command::Ir | Command::Cfg) {
+            fields.push(("stats", stats.to_json()));
+        }
+        fields.push(("diagnostics", diagnostics.to_json()));
+        println!("{}", Json::object(fields));
+    }
+    (diagnostics, exit_code)
+}
+
+/// Checks `source` and lowers it into IR optimized at the requested level,
+/// calling `dump` like [`compiler::compile_with`]. With `--stats`, also
+/// compares the IR as lowered with the optimized IR.
+fn compile_ir(
+    options: &Options,
+    source: &str,
+    mut dump: impl FnMut(Option<Pass>, &ir::Module),
+) -> (compiler::Compiled, Option<Stats>) {
+    let mut lowered = None;
+    let compiled =
+        compiler::compile_with(source, options.lints, options.opt_level, |pass, module| {
+            if options.stats && pass.is_none() {
+                lowered = Some(module.clone());
+            }
+            dump(pass, module);
+        });
+    let stats = lowered
+        .zip(compiled.module.as_ref())
+        .map(|(lowered, optimized)| Stats::new(&lowered, optimized));
+    (compiled, stats)
+}
+
+fn print_tree(root: NodeRef<SymbolTree>) {
+    // Deep trees, like long expressions, would overflow the stack with
+    // recursion.
+    let mut stack = vec![(root, 0)];
+    while let Some((node, depth)) = stack.pop() {
+        let span = node.data().span();
+        match node.data() {
+            SymbolTree::NonTerminal(non_terminal, _) => println!(
+                "{:indent$}{} {}..{}",
+                "",
+                non_terminal.as_ref(),
+                span.start,
+                span.end,
+                indent = depth * 2
+            ),
+            SymbolTree::Token(token) => println!(
+                "{:indent$}{} -> {} {}..{}",
+                "",
+                token.literal,
+                token.token.as_ref(),
+                span.start,
+                span.end,
+                indent = depth * 2
+            ),
+            SymbolTree::Error(_) => println!(
+                "{:indent$}<error> {}..{}",
+                "",
+                span.start,
+                span.end,
+                indent = depth * 2
+            ),
+        }
+        let children: Vec<_> = node.children().collect();
+        stack.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+    }
 }
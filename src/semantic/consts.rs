@@ -0,0 +1,268 @@
+//! Constant expressions, made of literals and operators only. Their values
+//! are `int`s, with `false` and `true` as `0` and `1` and a `char` as its
+//! code point, like in the [IR](crate::ir).
+
+use std::collections::HashMap;
+
+use crate::{
+    ast::{BinaryOp, Else, Expr, Ident, Item, Literal, Program, Stmt, UnaryOp, VarDecl},
+    diagnostic::{code, Diagnostic, Diagnostics},
+    token::Span,
+};
+
+use super::{
+    symbols::{SymbolKind, SymbolTable},
+    types::Type,
+    ExprTypes,
+};
+
+/// Whether `expr` is made of literals and operators only.
+pub fn is_constant(expr: &Expr) -> bool {
+    match expr {
+        Expr::Literal(literal, _) => !matches!(literal, Literal::String(_)),
+        Expr::Unary { operand, .. } => is_constant(operand),
+        Expr::Binary { lhs, rhs, .. } => is_constant(lhs) && is_constant(rhs),
+        Expr::Var(_) | Expr::Index { .. } | Expr::Call { .. } | Expr::Error(_) => false,
+    }
+}
+
+/// The value of `expr`, or `None` when it is not constant or evaluating it
+/// divides by zero or overflows. `expr` has to be well typed.
+pub fn eval(expr: &Expr) -> Option<i32> {
+    eval_with(expr, &|_| None)
+}
+
+/// The value of `expr` like [`eval`], with `var` giving the values of the
+/// variables it knows.
+fn eval_with(expr: &Expr, var: &dyn Fn(&Ident) -> Option<i32>) -> Option<i32> {
+    match expr {
+        Expr::Literal(literal, _) => match literal {
+            Literal::Int(n) => Some(*n),
+            Literal::Bool(b) => Some(i32::from(*b)),
+            Literal::Char(c) => Some(*c as i32),
+            Literal::String(_) => None,
+        },
+        Expr::Unary {
+            op: UnaryOp::Not,
+            operand,
+            ..
+        } => Some(i32::from(eval_with(operand, var)? == 0)),
+        Expr::Binary {
+            op: BinaryOp::And,
+            lhs,
+            rhs,
+            ..
+        } => Some(i32::from(
+            eval_with(lhs, var)? != 0 && eval_with(rhs, var)? != 0,
+        )),
+        Expr::Binary {
+            op: BinaryOp::Or,
+            lhs,
+            rhs,
+            ..
+        } => Some(i32::from(
+            eval_with(lhs, var)? != 0 || eval_with(rhs, var)? != 0,
+        )),
+        Expr::Binary { op, lhs, rhs, .. } => {
+            binary(*op, eval_with(lhs, var)?, eval_with(rhs, var)?)
+        }
+        Expr::Var(name) => var(name),
+        Expr::Index { .. } | Expr::Call { .. } | Expr::Error(_) => None,
+    }
+}
+
+/// Applies an operator other than `&&` and `||`, or returns `None` when it
+/// divides by zero or overflows.
+pub fn binary(op: BinaryOp, lhs: i32, rhs: i32) -> Option<i32> {
+    match op {
+        BinaryOp::Add => lhs.checked_add(rhs),
+        BinaryOp::Sub => lhs.checked_sub(rhs),
+        BinaryOp::Mul => lhs.checked_mul(rhs),
+        BinaryOp::Div => lhs.checked_div(rhs),
+        BinaryOp::Rem => lhs.checked_rem(rhs),
+        BinaryOp::Eq => Some(i32::from(lhs == rhs)),
+        BinaryOp::Ne => Some(i32::from(lhs != rhs)),
+        BinaryOp::Lt => Some(i32::from(lhs < rhs)),
+        BinaryOp::Le => Some(i32::from(lhs <= rhs)),
+        BinaryOp::Gt => Some(i32::from(lhs > rhs)),
+        BinaryOp::Ge => Some(i32::from(lhs >= rhs)),
+        BinaryOp::And | BinaryOp::Or => None,
+    }
+}
+
+/// Reports the arithmetic in `program` that divides by a constant zero or
+/// overflows with constant operands. A variable that is never assigned is
+/// constant as well: a local with a constant initializer, or a global with
+/// one or without any, which starts out as zero. `symbols` and `types` have
+/// to be those checking `program` found, with every assignment counted.
+pub fn check(
+    program: &Program,
+    symbols: &SymbolTable,
+    types: &ExprTypes,
+    diagnostics: &mut Diagnostics,
+) {
+    let mut consts = Consts {
+        symbols,
+        types,
+        values: HashMap::new(),
+        diagnostics,
+    };
+    for item in &program.items {
+        match item {
+            Item::Function(function) => consts.stmts(&function.body.stmts),
+            Item::Var(var) => consts.var(var, true),
+        }
+    }
+}
+
+struct Consts<'a> {
+    symbols: &'a SymbolTable,
+    types: &'a ExprTypes,
+    /// The values of the constant scalar variables declared so far, by the
+    /// span of their declaration.
+    values: HashMap<Span, i32>,
+    diagnostics: &'a mut Diagnostics,
+}
+
+impl Consts<'_> {
+    fn var(&mut self, var: &VarDecl, global: bool) {
+        for dim in &var.dims {
+            self.expr(dim);
+        }
+        let value = match &var.init {
+            Some(init) => {
+                self.expr(init);
+                self.eval(init)
+            }
+            None if global => Some(0),
+            None => None,
+        };
+        if let (Some(value), true) = (value, var.dims.is_empty()) {
+            self.values.insert(var.name.span, value);
+        }
+    }
+
+    fn stmts(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            self.stmt(stmt);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Var(var) => self.var(var, false),
+            Stmt::Assign { target, value, .. } => {
+                self.expr(target);
+                self.expr(value);
+            }
+            Stmt::ExprStmt { expr, .. } | Stmt::Return { value: expr, .. } => self.expr(expr),
+            Stmt::If {
+                cond,
+                then,
+                otherwise,
+                ..
+            } => {
+                self.expr(cond);
+                self.stmts(&then.stmts);
+                match otherwise {
+                    Some(Else::If(stmt)) => self.stmt(stmt),
+                    Some(Else::Block(block)) => self.stmts(&block.stmts),
+                    None => {}
+                }
+            }
+            Stmt::For {
+                init,
+                cond,
+                update,
+                body,
+                ..
+            } => {
+                self.stmts(init);
+                if let Some(cond) = cond {
+                    self.expr(cond);
+                }
+                if let Some(update) = update {
+                    self.stmt(update);
+                }
+                self.stmts(&body.stmts);
+            }
+            Stmt::Print { args, .. } => {
+                for arg in args {
+                    self.expr(arg);
+                }
+            }
+            Stmt::Break(_) | Stmt::Continue(_) => {}
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Index { array, index, .. } => {
+                self.expr(array);
+                self.expr(index);
+            }
+            Expr::Call { args, .. } => {
+                for arg in args {
+                    self.expr(arg);
+                }
+            }
+            Expr::Unary { operand, .. } => self.expr(operand),
+            Expr::Binary {
+                op, lhs, rhs, span, ..
+            } => {
+                self.expr(lhs);
+                self.expr(rhs);
+                let int = |operand: &Expr| self.types.get(&operand.span()) == Some(&Type::Int);
+                let arithmetic = matches!(
+                    op,
+                    BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem
+                );
+                if arithmetic && int(lhs) && int(rhs) {
+                    if let Some(error) = self.arithmetic(*op, lhs, rhs, *span) {
+                        self.diagnostics.push(error);
+                    }
+                }
+            }
+            Expr::Literal(..) | Expr::Var(_) | Expr::Error(_) => {}
+        }
+    }
+
+    /// The value of `expr`, with the constant variables it reads.
+    fn eval(&self, expr: &Expr) -> Option<i32> {
+        eval_with(expr, &|name| {
+            let symbol = &self.symbols[self.symbols.reference(name.span)?];
+            if symbol.kind != SymbolKind::Var || symbol.writes > 0 {
+                return None;
+            }
+            self.values.get(&symbol.span).copied()
+        })
+    }
+
+    /// Reports the arithmetic `lhs op rhs` at `span` when it divides by a
+    /// constant zero or when both operands are constant and it overflows.
+    fn arithmetic(&self, op: BinaryOp, lhs: &Expr, rhs: &Expr, span: Span) -> Option<Diagnostic> {
+        let divisor = self.eval(rhs);
+        if matches!(op, BinaryOp::Div | BinaryOp::Rem) && divisor == Some(0) {
+            let message = if op == BinaryOp::Div {
+                "attempt to divide by zero"
+            } else {
+                "attempt to calculate the remainder with a divisor of zero"
+            };
+            let mut error =
+                Diagnostic::error(code::CONSTANT_DIVISION_BY_ZERO, message).with_span(span);
+            if !matches!(rhs, Expr::Literal(..)) {
+                error = error.with_label(rhs.span(), format!("`{rhs}` is always `0`"));
+            }
+            return Some(error);
+        }
+        let (lhs, rhs) = (self.eval(lhs)?, divisor?);
+        binary(op, lhs, rhs).is_none().then(|| {
+            Diagnostic::error(
+                code::CONSTANT_OVERFLOW,
+                format!("`{lhs} {op} {rhs}` overflows `int`"),
+            )
+            .with_span(span)
+            .with_note(format!("an `int` is between {} and {}", i32::MIN, i32::MAX))
+        })
+    }
+}
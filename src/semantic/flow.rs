@@ -0,0 +1,185 @@
+//! Checks how control flows through a function body: every path has to end
+//! in a `return`, and statements that can never run are warned about.
+
+use crate::{
+    ast::{Block, Else, Expr, FunctionDecl, Literal, Stmt},
+    diagnostic::{code, Diagnostic, Diagnostics},
+};
+
+use super::LoopTargets;
+
+/// How control can leave a statement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Flow {
+    /// Whether control can go on to the next statement.
+    completes: bool,
+    /// Whether a `break` can leave the innermost enclosing loop.
+    breaks: bool,
+}
+
+impl Flow {
+    const NEXT: Flow = Flow {
+        completes: true,
+        breaks: false,
+    };
+    const NEVER: Flow = Flow {
+        completes: false,
+        breaks: false,
+    };
+}
+
+/// Reports a missing `return` at the end of `function` when its end can be
+/// reached, and the first statement of every statement list that follows a
+/// statement it can never get past. Every function returns a value, there
+/// are no `void` functions.
+///
+/// A `break` or `continue` without an entry in `loops` is not inside a loop
+/// and has been reported already, it is treated like any other statement.
+pub fn check_function(function: &FunctionDecl, loops: &LoopTargets, diagnostics: &mut Diagnostics) {
+    if block(&function.body, loops, diagnostics).completes {
+        let name = &function.name.name;
+        diagnostics.push(
+            Diagnostic::error(
+                code::MISSING_RETURN,
+                format!("missing `return` at the end of `{name}`"),
+            )
+            .with_span(function.body.close)
+            .with_label(
+                function.name.span,
+                format!("`{name}` returns `{}`", function.return_type),
+            )
+            .with_note("every path through a function must end in a `return`"),
+        );
+    }
+}
+
+fn block(block: &Block, loops: &LoopTargets, diagnostics: &mut Diagnostics) -> Flow {
+    stmts(&block.stmts, loops, diagnostics)
+}
+
+fn stmts(stmts: &[Stmt], loops: &LoopTargets, diagnostics: &mut Diagnostics) -> Flow {
+    let mut flow = Flow::NEXT;
+    for (i, stmt) in stmts.iter().enumerate() {
+        let next = self::stmt(stmt, loops, diagnostics);
+        flow = Flow {
+            completes: next.completes,
+            breaks: flow.breaks || next.breaks,
+        };
+        if !flow.completes {
+            if let Some(unreachable) = stmts.get(i + 1) {
+                diagnostics.push(
+                    Diagnostic::warning(code::UNREACHABLE_CODE, "unreachable statement")
+                        .with_span(unreachable.span())
+                        .with_label(
+                            stmt.span(),
+                            format!("any code after this `{}` is unreachable", keyword(stmt)),
+                        ),
+                );
+            }
+            break;
+        }
+    }
+    flow
+}
+
+fn stmt(stmt: &Stmt, loops: &LoopTargets, diagnostics: &mut Diagnostics) -> Flow {
+    match stmt {
+        Stmt::Break(span) | Stmt::Continue(span) if !loops.contains_key(span) => Flow::NEXT,
+        Stmt::Return { .. } | Stmt::Continue(_) => Flow::NEVER,
+        Stmt::Break(_) => Flow {
+            completes: false,
+            breaks: true,
+        },
+        Stmt::If {
+            then, otherwise, ..
+        } => {
+            let then = block(then, loops, diagnostics);
+            let otherwise = match otherwise {
+                Some(Else::If(stmt)) => self::stmt(stmt, loops, diagnostics),
+                Some(Else::Block(otherwise)) => block(otherwise, loops, diagnostics),
+                None => Flow::NEXT,
+            };
+            Flow {
+                completes: then.completes || otherwise.completes,
+                breaks: then.breaks || otherwise.breaks,
+            }
+        }
+        Stmt::For { cond, body, .. } => {
+            let body = block(body, loops, diagnostics);
+            Flow {
+                completes: !is_endless(cond) || body.breaks,
+                breaks: false,
+            }
+        }
+        Stmt::Var(_) | Stmt::Assign { .. } | Stmt::ExprStmt { .. } | Stmt::Print { .. } => {
+            Flow::NEXT
+        }
+    }
+}
+
+/// Whether a `for` with the condition `cond` can only be left through a
+/// `break`, which is when it has no condition or `true`.
+pub fn is_endless(cond: &Option<Expr>) -> bool {
+    matches!(cond, None | Some(Expr::Literal(Literal::Bool(true), _)))
+}
+
+/// The keyword a statement that does not complete starts with.
+fn keyword(stmt: &Stmt) -> &'static str {
+    match stmt {
+        Stmt::Return { .. } => "return",
+        Stmt::Break(_) => "break",
+        Stmt::Continue(_) => "continue",
+        Stmt::If { .. } => "if",
+        Stmt::For { .. } => "for",
+        _ => "statement",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        diagnostic::{code, Lints},
+        semantic::tests::located,
+    };
+
+    #[test]
+    fn follows_every_path_to_a_return() {
+        let checked = crate::check_with(
+            "int sign(int x) {\n\
+             \x20 if (x < 0) { return 0 - 1; } else if (x == 0) { return 0; } else { return 1; }\n\
+             }\n\
+             int spin() { for (;;) { } }\n\
+             int search(int n) {\n\
+             \x20 for (int i = 0; i < n; i = i + 1) { if (i == 3) { return i; } }\n\
+             }\n\
+             int stop() { for (;;) { break; } }\n\
+             int partial(bool b) { if (b) { return 1; } else if (!b) { return 2; } }\n\
+             int main() {\n\
+             \x20 for (int i = 0; i < 3; i = i + 1) {\n\
+             \x20   continue;\n\
+             \x20   print(i);\n\
+             \x20 }\n\
+             \x20 return 0;\n\
+             \x20 print(1);\n\
+             \x20 return 1;\n\
+             }",
+            Lints::allow_all(),
+        );
+        assert_eq!(
+            located(&checked.diagnostics),
+            [
+                (code::MISSING_RETURN, 7, 1),
+                (code::MISSING_RETURN, 8, 34),
+                (code::MISSING_RETURN, 9, 71),
+                (code::UNREACHABLE_CODE, 13, 5),
+                (code::UNREACHABLE_CODE, 16, 3),
+            ]
+        );
+        let unreachable = checked.diagnostics.iter().last().unwrap();
+        assert_eq!(
+            unreachable.labels[0].message,
+            "any code after this `return` is unreachable"
+        );
+        assert_eq!(checked.diagnostics.error_count(), 3);
+    }
+}
@@ -0,0 +1,279 @@
+//! Definite assignment: a local variable declared without a value has to be
+//! assigned one on every path to a read of it. Globals start out as zero,
+//! and arrays are not tracked.
+
+use std::{collections::HashSet, mem};
+
+use crate::{
+    ast::{Block, Else, Expr, FunctionDecl, Stmt},
+    diagnostic::{code, Diagnostic, Diagnostics},
+    token::Span,
+};
+
+use super::{flow::is_endless, symbols::SymbolTable, types::Type, ExprTypes};
+
+/// The local variables that might not have a value yet, by the span of
+/// their declaration. `None` where the code cannot be reached, which is
+/// where every variable can be taken to have a value.
+type Unassigned = Option<HashSet<Span>>;
+
+/// Reports the first read of every local variable of `function` that might
+/// not have been assigned a value. `symbols` has to have the names in the
+/// body resolved, and `types` the types of its expressions.
+pub fn check_function(
+    function: &FunctionDecl,
+    symbols: &SymbolTable,
+    types: &ExprTypes,
+    diagnostics: &mut Diagnostics,
+) {
+    let mut init = Init {
+        symbols,
+        types,
+        unassigned: Some(HashSet::new()),
+        loops: vec![],
+        diagnostics,
+    };
+    init.block(&function.body);
+}
+
+/// What is unassigned at the `break`s and `continue`s of a loop.
+#[derive(Default)]
+struct Exits {
+    breaks: Unassigned,
+    continues: Unassigned,
+}
+
+struct Init<'a> {
+    symbols: &'a SymbolTable,
+    types: &'a ExprTypes,
+    unassigned: Unassigned,
+    /// The loops around the current statement, innermost last.
+    loops: Vec<Exits>,
+    diagnostics: &'a mut Diagnostics,
+}
+
+impl Init<'_> {
+    fn block(&mut self, block: &Block) {
+        self.stmts(&block.stmts);
+    }
+
+    fn stmts(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            self.stmt(stmt);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Var(var) => match &var.init {
+                Some(init) => self.read(init),
+                None if var.dims.is_empty() => {
+                    if let Some(unassigned) = &mut self.unassigned {
+                        unassigned.insert(var.name.span);
+                    }
+                }
+                None => {}
+            },
+            Stmt::Assign { target, value, .. } => {
+                self.read(value);
+                match target {
+                    Expr::Var(name) => {
+                        let declaration = self.declaration(name.span);
+                        if let (Some(unassigned), Some(declaration)) =
+                            (&mut self.unassigned, declaration)
+                        {
+                            unassigned.remove(&declaration);
+                        }
+                    }
+                    _ => self.read(target),
+                }
+            }
+            Stmt::ExprStmt { expr, .. } => self.read(expr),
+            Stmt::If {
+                cond,
+                then,
+                otherwise,
+                ..
+            } => {
+                self.read(cond);
+                let before = self.unassigned.clone();
+                self.block(then);
+                let after_then = mem::replace(&mut self.unassigned, before);
+                match otherwise {
+                    Some(Else::If(stmt)) => self.stmt(stmt),
+                    Some(Else::Block(block)) => self.block(block),
+                    None => {}
+                }
+                self.unassigned = join(after_then, self.unassigned.take());
+            }
+            Stmt::For {
+                init,
+                cond,
+                update,
+                body,
+                ..
+            } => {
+                self.stmts(init);
+                if let Some(cond) = cond {
+                    self.read(cond);
+                }
+                // The body might not run at all, and running it again cannot
+                // unassign anything, so only the first time through counts.
+                let before = self.unassigned.clone();
+                self.loops.push(Exits::default());
+                self.block(body);
+                let exits = self.loops.pop().expect("pushed above");
+                if let Some(update) = update {
+                    self.unassigned = join(self.unassigned.take(), exits.continues);
+                    self.stmt(update);
+                }
+                self.unassigned = if is_endless(cond) {
+                    exits.breaks
+                } else {
+                    before
+                };
+            }
+            Stmt::Return { value, .. } => {
+                self.read(value);
+                self.unassigned = None;
+            }
+            Stmt::Print { args, .. } => {
+                for arg in args {
+                    self.read(arg);
+                }
+            }
+            Stmt::Break(_) | Stmt::Continue(_) => {
+                // Outside a loop it has been reported and changes nothing.
+                let Some(exits) = self.loops.last_mut() else {
+                    return;
+                };
+                let exit = match stmt {
+                    Stmt::Break(_) => &mut exits.breaks,
+                    _ => &mut exits.continues,
+                };
+                *exit = join(exit.take(), self.unassigned.take());
+            }
+        }
+    }
+
+    /// Reports the variables `expr` reads that might be unassigned.
+    fn read(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Var(name) => {
+                let Some(declaration) = self.declaration(name.span) else {
+                    return;
+                };
+                let Some(unassigned) = &mut self.unassigned else {
+                    return;
+                };
+                // Taken to be assigned from here on, so it is reported once.
+                if unassigned.remove(&declaration) {
+                    self.diagnostics.push(
+                        Diagnostic::error(
+                            code::UNINITIALIZED,
+                            format!("`{}` might be read before it is assigned", name.name),
+                        )
+                        .with_span(name.span)
+                        .with_label(
+                            declaration,
+                            format!("`{}` is declared here without a value", name.name),
+                        ),
+                    );
+                }
+            }
+            Expr::Index { array, index, .. } => {
+                // Indexing something that is not an array has been reported.
+                if matches!(self.types.get(&array.span()), Some(Type::Array(..))) {
+                    self.read(array);
+                }
+                self.read(index);
+            }
+            Expr::Call { args, .. } => {
+                for arg in args {
+                    self.read(arg);
+                }
+            }
+            Expr::Unary { operand, .. } => self.read(operand),
+            Expr::Binary { lhs, rhs, .. } => {
+                self.read(lhs);
+                self.read(rhs);
+            }
+            Expr::Literal(..) | Expr::Error(_) => {}
+        }
+    }
+
+    /// Where the variable the name at `span` refers to is declared.
+    fn declaration(&self, span: Span) -> Option<Span> {
+        self.symbols
+            .reference(span)
+            .map(|symbol| self.symbols[symbol].span)
+    }
+}
+
+/// What is unassigned where two paths meet.
+fn join(a: Unassigned, b: Unassigned) -> Unassigned {
+    match (a, b) {
+        (Some(mut a), Some(b)) => {
+            a.extend(b);
+            Some(a)
+        }
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        diagnostic::{code, Lints},
+        semantic::tests::located,
+    };
+
+    #[test]
+    fn reports_reads_that_might_come_before_an_assignment() {
+        let checked = crate::check_with(
+            "int g;\n\
+             int f(int p) {\n\
+             \x20 int a, b, c, d, e, n[2];\n\
+             \x20 if (p > 0) { a = 1; b = 1; } else if (p < 0) { a = 2; } else { return n[0]; }\n\
+             \x20 print(g, p, a, b, b);\n\
+             \x20 for (int i = 0; i < p; i = i + 1) { c = i; d = 1; }\n\
+             \x20 print(c);\n\
+             \x20 for (;;) { if (p == 1) { e = 1; break; } d = 2; }\n\
+             \x20 return d + e;\n\
+             }\n\
+             int main() {\n\
+             \x20 int x;\n\
+             \x20 x = x + 1;\n\
+             \x20 return f(x);\n\
+             }",
+            Lints::allow_all(),
+        );
+        assert_eq!(
+            located(&checked.diagnostics),
+            [
+                (code::UNINITIALIZED, 5, 18),
+                (code::UNINITIALIZED, 7, 9),
+                (code::UNINITIALIZED, 9, 10),
+                (code::UNINITIALIZED, 13, 7),
+            ]
+        );
+        let first = checked.diagnostics.iter().next().unwrap();
+        assert_eq!(first.message, "`b` might be read before it is assigned");
+        assert_eq!(
+            (first.labels[0].span.line, first.labels[0].span.column),
+            (3, 10)
+        );
+    }
+    #[test]
+    fn skips_what_is_indexed_without_being_an_array() {
+        let checked = crate::check_with(
+            "int main() { int x; x[0] = 1; return x[1]; }",
+            Lints::allow_all(),
+        );
+        assert_eq!(
+            checked.diagnostics.codes(),
+            [code::TYPE_MISMATCH, code::TYPE_MISMATCH]
+        );
+    }
+}
@@ -0,0 +1,897 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::{
+        BinaryOp, Block, Else, Expr, FunctionDecl, Ident, Item, Program, Stmt, TypeName, UnaryOp,
+        VarDecl,
+    },
+    diagnostic::{code, Diagnostic, Diagnostics, Lints},
+    token::Span,
+};
+
+pub mod consts;
+pub mod flow;
+pub mod init;
+pub mod symbols;
+pub mod types;
+pub mod unused;
+
+use symbols::{ScopeKind, SymbolId, SymbolKind, SymbolTable};
+use types::Type;
+
+/// The type of every checked expression, by the span of the expression.
+pub type ExprTypes = HashMap<Span, Type>;
+
+/// The functions each function calls, under `None` the ones the
+/// initializers of globals call.
+pub type Calls = HashMap<Option<SymbolId>, Vec<SymbolId>>;
+
+/// The `for` statement every `break` and `continue` leaves or goes on with,
+/// by the span of the `break` or `continue`.
+pub type LoopTargets = HashMap<Span, Span>;
+
+/// Runs the semantic checks over a [`Program`], resolving every name in the
+/// [`SymbolTable`] it builds and assigning a [`Type`] to every expression
+/// bottom-up.
+#[derive(Default)]
+pub struct Sem {
+    symbols: SymbolTable,
+    types: ExprTypes,
+    loop_targets: LoopTargets,
+    /// The spans of the loops around the statement being checked, innermost
+    /// last.
+    loops: Vec<Span>,
+    calls: Calls,
+    /// Calls to names that were not declared at the call, with their caller.
+    undeclared_calls: Vec<(Option<SymbolId>, String)>,
+    /// The function whose body is being checked.
+    function: Option<SymbolId>,
+    lints: Lints,
+}
+
+impl Sem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reports the unused declarations [`unused`] looks for at the levels in
+    /// `lints`.
+    pub fn with_lints(lints: Lints) -> Self {
+        Self {
+            lints,
+            ..Self::default()
+        }
+    }
+
+    /// Runs the checks, reporting what they find into `diagnostics`.
+    pub fn check(&mut self, program: &Program, diagnostics: &mut Diagnostics) {
+        for item in &program.items {
+            match item {
+                Item::Function(function) => self.function(function, diagnostics),
+                Item::Var(var) => self.var_decl(var, diagnostics),
+            }
+        }
+        let has_main = program.items.iter().any(|item| {
+            matches!(item, Item::Function(function)
+                if function.name.name == "main"
+                    && function.return_type == TypeName::Int
+                    && function.params.is_empty())
+        });
+        if !has_main {
+            diagnostics.push(Diagnostic::error(
+                code::MISSING_MAIN,
+                "there should be an `int main()` function without parameters",
+            ));
+        }
+        // A function declared after a call to it, which was reported as not
+        // declared, is not reported as never called as well.
+        for (caller, name) in std::mem::take(&mut self.undeclared_calls) {
+            let called = self
+                .symbols
+                .lookup(&name)
+                .filter(|&called| self.symbols[called].kind == SymbolKind::Func);
+            if let Some(called) = called {
+                self.calls.entry(caller).or_default().push(called);
+            }
+        }
+        // Once every assignment is counted, to know which variables are constant.
+        consts::check(program, &self.symbols, &self.types, diagnostics);
+        unused::check(&self.symbols, &self.calls, &self.lints, diagnostics);
+    }
+
+    pub fn symbols(&self) -> &SymbolTable {
+        &self.symbols
+    }
+
+    pub fn types(&self) -> &ExprTypes {
+        &self.types
+    }
+
+    pub fn loop_targets(&self) -> &LoopTargets {
+        &self.loop_targets
+    }
+
+    pub fn finish(self) -> (SymbolTable, ExprTypes, LoopTargets) {
+        (self.symbols, self.types, self.loop_targets)
+    }
+
+    fn declare(&mut self, name: &Ident, kind: SymbolKind, ty: Type, diagnostics: &mut Diagnostics) {
+        if let Err(existing) = self.symbols.declare(&name.name, kind, ty, name.span) {
+            diagnostics.push(
+                Diagnostic::error(
+                    code::DUPLICATE_DECLARATION,
+                    format!("`{}` is already declared in this block", name.name),
+                )
+                .with_span(name.span)
+                .with_label(self.symbols[existing].span, "first declared here"),
+            );
+        }
+    }
+
+    /// The type of what `name` refers to, reporting it when it is not
+    /// declared.
+    fn resolve(&mut self, name: &Ident, diagnostics: &mut Diagnostics) -> Type {
+        match self.symbols.resolve(&name.name, name.span) {
+            Some(symbol) => self.symbols[symbol].ty.clone(),
+            None => {
+                diagnostics.push(
+                    Diagnostic::error(code::UNDECLARED, format!("`{}` is not declared", name.name))
+                        .with_span(name.span),
+                );
+                Type::Error
+            }
+        }
+    }
+
+    fn function(&mut self, function: &FunctionDecl, diagnostics: &mut Diagnostics) {
+        let ty = Type::Function {
+            params: function
+                .params
+                .iter()
+                .map(|param| param.ty.into())
+                .collect(),
+            ret: Box::new(function.return_type.into()),
+        };
+        // Declared before the body so it can call itself.
+        self.declare(&function.name, SymbolKind::Func, ty, diagnostics);
+        self.function = self.symbols.lookup(&function.name.name);
+
+        self.symbols.push_scope(ScopeKind::Function, function.span);
+        for param in &function.params {
+            self.declare(&param.name, SymbolKind::Param, param.ty.into(), diagnostics);
+        }
+        self.stmts(&function.body.stmts, function, diagnostics);
+        self.symbols.pop_scope();
+        flow::check_function(function, &self.loop_targets, diagnostics);
+        init::check_function(function, &self.symbols, &self.types, diagnostics);
+        self.function = None;
+    }
+
+    fn var_decl(&mut self, var: &VarDecl, diagnostics: &mut Diagnostics) {
+        let sizes: Vec<Option<usize>> = var
+            .dims
+            .iter()
+            .map(|dim| self.array_size(dim, diagnostics))
+            .collect();
+        let mut ty = Type::from(var.ty);
+        for size in sizes.into_iter().rev() {
+            ty = match size {
+                Some(size) => Type::Array(Box::new(ty), size),
+                None => Type::Error,
+            };
+        }
+        // Declared after its initializer, so `int x = x;` refers to an outer `x`.
+        if let Some(init) = &var.init {
+            let found = self.expr(init, diagnostics);
+            if let Type::Array(..) = ty {
+                // Arrays have no initializers, not even `char s[3] = "ab";`.
+                diagnostics.push(
+                    Diagnostic::error(
+                        code::TYPE_MISMATCH,
+                        format!("cannot initialize `{}` of type `{ty}`", var.name.name),
+                    )
+                    .with_span(init.span())
+                    .with_label(var.name.span, format!("`{}` is an array", var.name.name))
+                    .with_note("arrays start out as zero and are assigned one element at a time"),
+                );
+            } else if !found.matches(&ty) {
+                diagnostics.push(mismatch(&ty, &found, init.span()).with_label(
+                    var.name.span,
+                    format!("`{}` is declared as `{ty}`", var.name.name),
+                ));
+            }
+        }
+        let kind = if var.dims.is_empty() {
+            SymbolKind::Var
+        } else {
+            SymbolKind::Array
+        };
+        self.declare(&var.name, kind, ty, diagnostics);
+    }
+
+    /// The size of an array dimension, which has to be a constant `int`
+    /// bigger than 0.
+    fn array_size(&mut self, dim: &Expr, diagnostics: &mut Diagnostics) -> Option<usize> {
+        let ty = self.expr(dim, diagnostics);
+        if ty.is_error() {
+            return None;
+        }
+        if ty != Type::Int {
+            diagnostics
+                .push(mismatch(&Type::Int, &ty, dim.span()).with_note("array sizes must be `int`"));
+            return None;
+        }
+        if !consts::is_constant(dim) {
+            diagnostics.push(
+                Diagnostic::error(
+                    code::INVALID_ARRAY_SIZE,
+                    "array size should be a constant expression",
+                )
+                .with_span(dim.span())
+                .with_note("array sizes can only be made of literals and operators"),
+            );
+            return None;
+        }
+        // `None` here divides by zero or overflows, which is reported.
+        let size = consts::eval(dim)?;
+        if size <= 0 {
+            let mut error = Diagnostic::error(
+                code::INVALID_ARRAY_SIZE,
+                "array size should be bigger than 0",
+            )
+            .with_span(dim.span());
+            if !matches!(dim, Expr::Literal(..)) {
+                error = error.with_note(format!("`{dim}` is `{size}`"));
+            }
+            diagnostics.push(error);
+            return None;
+        }
+        Some(size as usize)
+    }
+
+    fn block(&mut self, block: &Block, function: &FunctionDecl, diagnostics: &mut Diagnostics) {
+        self.symbols.push_scope(ScopeKind::Block, block.span);
+        self.stmts(&block.stmts, function, diagnostics);
+        self.symbols.pop_scope();
+    }
+
+    fn stmts(&mut self, stmts: &[Stmt], function: &FunctionDecl, diagnostics: &mut Diagnostics) {
+        for stmt in stmts {
+            self.stmt(stmt, function, diagnostics);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt, function: &FunctionDecl, diagnostics: &mut Diagnostics) {
+        match stmt {
+            Stmt::Var(var) => self.var_decl(var, diagnostics),
+            Stmt::Assign { target, value, .. } => {
+                let target_ty = self.expr(target, diagnostics);
+                let value_ty = self.expr(value, diagnostics);
+                let assigned =
+                    if matches!(target_ty, Type::Array(..) | Type::Function { .. }) {
+                        diagnostics.push(
+                            Diagnostic::error(
+                                code::TYPE_MISMATCH,
+                                format!("cannot assign to `{target}` of type `{target_ty}`"),
+                            )
+                            .with_span(target.span())
+                            .with_note("only `int`, `bool` and `char` values can be assigned"),
+                        );
+                        false
+                    } else if !value_ty.matches(&target_ty) {
+                        diagnostics.push(mismatch(&target_ty, &value_ty, value.span()).with_label(
+                            target.span(),
+                            format!("`{target}` has type `{target_ty}`"),
+                        ));
+                        false
+                    } else {
+                        !target_ty.is_error()
+                    };
+                // `a[i] = x` writes to `a` and reads `i`. An assignment that is
+                // a type error is not counted as a write.
+                let mut written = target;
+                while let Expr::Index { array, .. } = written {
+                    written = array;
+                }
+                if let (Expr::Var(name), true) = (written, assigned) {
+                    self.symbols.record_write(name.span);
+                }
+            }
+            Stmt::ExprStmt { expr, .. } => {
+                self.expr(expr, diagnostics);
+            }
+            Stmt::If {
+                cond,
+                then,
+                otherwise,
+                ..
+            } => {
+                self.condition(cond, "if", diagnostics);
+                self.block(then, function, diagnostics);
+                match otherwise {
+                    Some(Else::If(stmt)) => self.stmt(stmt, function, diagnostics),
+                    Some(Else::Block(block)) => self.block(block, function, diagnostics),
+                    None => {}
+                }
+            }
+            Stmt::For {
+                init,
+                cond,
+                update,
+                body,
+                span,
+            } => {
+                self.symbols.push_scope(ScopeKind::ForInit, *span);
+                self.stmts(init, function, diagnostics);
+                if let Some(cond) = cond {
+                    self.condition(cond, "for", diagnostics);
+                }
+                if let Some(update) = update {
+                    self.stmt(update, function, diagnostics);
+                }
+                self.loops.push(*span);
+                self.block(body, function, diagnostics);
+                self.loops.pop();
+                self.symbols.pop_scope();
+            }
+            Stmt::Return { value, .. } => {
+                let expected = Type::from(function.return_type);
+                let found = self.expr(value, diagnostics);
+                if !found.matches(&expected) {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            code::RETURN_TYPE_MISMATCH,
+                            format!("expected `{expected}`, found `{found}`"),
+                        )
+                        .with_span(value.span())
+                        .with_label(
+                            function.name.span,
+                            format!("`{}` returns `{expected}`", function.name.name),
+                        ),
+                    );
+                }
+            }
+            Stmt::Print { args, .. } => {
+                for arg in args {
+                    let ty = self.expr(arg, diagnostics);
+                    if !(ty.is_scalar() || ty == Type::String || ty.is_error()) {
+                        diagnostics.push(
+                            Diagnostic::error(
+                                code::TYPE_MISMATCH,
+                                format!("cannot print a value of type `{ty}`"),
+                            )
+                            .with_span(arg.span()),
+                        );
+                    }
+                }
+            }
+            Stmt::Break(span) => self.loop_control(*span, "break", diagnostics),
+            Stmt::Continue(span) => self.loop_control(*span, "continue", diagnostics),
+        }
+    }
+
+    /// Records the loop a `break` or `continue` at `span` belongs to, or
+    /// reports it when it is not inside one.
+    fn loop_control(&mut self, span: Span, keyword: &str, diagnostics: &mut Diagnostics) {
+        match self.loops.last() {
+            Some(&target) => {
+                self.loop_targets.insert(span, target);
+            }
+            None => diagnostics.push(
+                Diagnostic::error(
+                    code::LOOP_CONTROL_OUTSIDE_LOOP,
+                    format!("`{keyword}` outside of a loop"),
+                )
+                .with_span(span)
+                .with_note(format!("`{keyword}` can only be used inside a `for`")),
+            ),
+        }
+    }
+
+    /// Checks that the condition of an `if` or a `for` is a `bool`.
+    fn condition(&mut self, cond: &Expr, statement: &str, diagnostics: &mut Diagnostics) {
+        let ty = self.expr(cond, diagnostics);
+        if !ty.matches(&Type::Bool) {
+            diagnostics.push(
+                mismatch(&Type::Bool, &ty, cond.span())
+                    .with_note(format!("the condition of a `{statement}` must be a `bool`")),
+            );
+        }
+    }
+
+    /// Checks `expr` and returns its type, which is also recorded in
+    /// [`Sem::types`].
+    fn expr(&mut self, expr: &Expr, diagnostics: &mut Diagnostics) -> Type {
+        let ty = self.expr_type(expr, diagnostics);
+        self.types.insert(expr.span(), ty.clone());
+        ty
+    }
+
+    fn expr_type(&mut self, expr: &Expr, diagnostics: &mut Diagnostics) -> Type {
+        match expr {
+            Expr::Literal(literal, _) => Type::of_literal(literal),
+            Expr::Var(ident) => self.resolve(ident, diagnostics),
+            Expr::Index { array, index, .. } => {
+                let array_ty = self.expr(array, diagnostics);
+                let index_ty = self.expr(index, diagnostics);
+                if !index_ty.matches(&Type::Int) {
+                    diagnostics.push(
+                        mismatch(&Type::Int, &index_ty, index.span())
+                            .with_note("array indices must be `int`"),
+                    );
+                }
+                match array_ty {
+                    Type::Array(element, _) => *element,
+                    Type::Error => Type::Error,
+                    other => {
+                        diagnostics.push(
+                            Diagnostic::error(
+                                code::TYPE_MISMATCH,
+                                format!("cannot index into `{array}` of type `{other}`"),
+                            )
+                            .with_span(array.span()),
+                        );
+                        Type::Error
+                    }
+                }
+            }
+            Expr::Call { callee, args, .. } => {
+                let arg_types: Vec<Type> =
+                    args.iter().map(|arg| self.expr(arg, diagnostics)).collect();
+                match self.resolve(callee, diagnostics) {
+                    Type::Function { params, ret } => {
+                        if let Some(called) = self.symbols.reference(callee.span) {
+                            self.calls.entry(self.function).or_default().push(called);
+                        }
+                        self.call_args(callee, &params, args, &arg_types, diagnostics);
+                        *ret
+                    }
+                    Type::Error => {
+                        if self.symbols.reference(callee.span).is_none() {
+                            self.undeclared_calls
+                                .push((self.function, callee.name.clone()));
+                        }
+                        Type::Error
+                    }
+                    _ => {
+                        diagnostics.push(
+                            Diagnostic::error(
+                                code::CALL_MISMATCH,
+                                format!("`{}` is not a function", callee.name),
+                            )
+                            .with_span(callee.span)
+                            .with_label(
+                                self.declaration_of(callee),
+                                format!("`{}` is declared here", callee.name),
+                            ),
+                        );
+                        Type::Error
+                    }
+                }
+            }
+            Expr::Unary {
+                op: UnaryOp::Not,
+                op_span,
+                operand,
+                ..
+            } => {
+                let ty = self.expr(operand, diagnostics);
+                expect_operand(&ty, &Type::Bool, operand, "!", *op_span, diagnostics);
+                Type::Bool
+            }
+            Expr::Binary {
+                op,
+                op_span,
+                lhs,
+                rhs,
+                ..
+            } => {
+                let lhs_ty = self.expr(lhs, diagnostics);
+                let rhs_ty = self.expr(rhs, diagnostics);
+                let op_str = op.as_str();
+                match op {
+                    BinaryOp::Add
+                    | BinaryOp::Sub
+                    | BinaryOp::Mul
+                    | BinaryOp::Div
+                    | BinaryOp::Rem => {
+                        expect_operand(&lhs_ty, &Type::Int, lhs, op_str, *op_span, diagnostics);
+                        expect_operand(&rhs_ty, &Type::Int, rhs, op_str, *op_span, diagnostics);
+                        Type::Int
+                    }
+                    BinaryOp::And | BinaryOp::Or => {
+                        expect_operand(&lhs_ty, &Type::Bool, lhs, op_str, *op_span, diagnostics);
+                        expect_operand(&rhs_ty, &Type::Bool, rhs, op_str, *op_span, diagnostics);
+                        Type::Bool
+                    }
+                    // Ordering compares two `int`s or two `char`s.
+                    BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => {
+                        let expected = if lhs_ty == Type::Char
+                            || (lhs_ty.is_error() && rhs_ty == Type::Char)
+                        {
+                            Type::Char
+                        } else {
+                            Type::Int
+                        };
+                        if expect_operand(&lhs_ty, &expected, lhs, op_str, *op_span, diagnostics) {
+                            expect_operand(&rhs_ty, &expected, rhs, op_str, *op_span, diagnostics);
+                        }
+                        Type::Bool
+                    }
+                    // Equality compares two values of the same scalar type.
+                    BinaryOp::Eq | BinaryOp::Ne => {
+                        if !(lhs_ty.is_scalar() || lhs_ty.is_error()) {
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    code::TYPE_MISMATCH,
+                                    format!("`{op_str}` cannot compare values of type `{lhs_ty}`"),
+                                )
+                                .with_span(lhs.span())
+                                .with_label(*op_span, "compared here"),
+                            );
+                        } else {
+                            expect_operand(&rhs_ty, &lhs_ty, rhs, op_str, *op_span, diagnostics);
+                        }
+                        Type::Bool
+                    }
+                }
+            }
+            Expr::Error(_) => Type::Error,
+        }
+    }
+
+    /// Checks the number of arguments of a call to `callee` and, when it is
+    /// right, the type of each argument against its parameter.
+    fn call_args(
+        &self,
+        callee: &Ident,
+        params: &[Type],
+        args: &[Expr],
+        arg_types: &[Type],
+        diagnostics: &mut Diagnostics,
+    ) {
+        let declaration = self.declaration_of(callee);
+        if params.len() != args.len() {
+            let plural = |n: usize| if n == 1 { "" } else { "s" };
+            diagnostics.push(
+                Diagnostic::error(
+                    code::CALL_MISMATCH,
+                    format!(
+                        "`{}` takes {} argument{} but {} {} given",
+                        callee.name,
+                        params.len(),
+                        plural(params.len()),
+                        args.len(),
+                        if args.len() == 1 { "was" } else { "were" }
+                    ),
+                )
+                .with_span(callee.span)
+                .with_label(declaration, format!("`{}` is declared here", callee.name)),
+            );
+            return;
+        }
+        for (i, ((arg, found), expected)) in args.iter().zip(arg_types).zip(params).enumerate() {
+            if !found.matches(expected) {
+                diagnostics.push(mismatch(expected, found, arg.span()).with_label(
+                    declaration,
+                    format!("parameter {} of `{}` is `{expected}`", i + 1, callee.name),
+                ));
+            }
+        }
+    }
+
+    /// Where the symbol `name` was resolved to is declared.
+    fn declaration_of(&self, name: &Ident) -> Span {
+        self.symbols
+            .reference(name.span)
+            .map_or(name.span, |symbol| self.symbols[symbol].span)
+    }
+}
+
+/// A type mismatch at `span`.
+fn mismatch(expected: &Type, found: &Type, span: Span) -> Diagnostic {
+    Diagnostic::error(
+        code::TYPE_MISMATCH,
+        format!("expected `{expected}`, found `{found}`"),
+    )
+    .with_span(span)
+}
+
+/// Reports `operand` of the operator `op` unless its type `ty` matches
+/// `expected`, and returns whether it matched.
+fn expect_operand(
+    ty: &Type,
+    expected: &Type,
+    operand: &Expr,
+    op: &str,
+    op_span: Span,
+    diagnostics: &mut Diagnostics,
+) -> bool {
+    if ty.matches(expected) {
+        return true;
+    }
+    diagnostics.push(
+        mismatch(expected, ty, operand.span())
+            .with_label(op_span, format!("`{op}` needs `{expected}` operands")),
+    );
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(source: &str) -> (SymbolTable, Diagnostics) {
+        let checked = crate::check_with(source, Lints::allow_all());
+        (checked.symbols, checked.diagnostics)
+    }
+
+    #[test]
+    fn resolves_names_in_nested_scopes() {
+        let (symbols, diagnostics) = check(
+            "int x;\n\
+             int f(int a) {\n\
+             \x20 int x = a;\n\
+             \x20 for (int i = 0; i < x; i = i + 1) { int i = 2; bool a = true; }\n\
+             \x20 if (true) { int y = 1; } else { int y = 2; }\n\
+             \x20 return y + i;\n\
+             }\n\
+             int main() { return f(x); }",
+        );
+        assert_eq!(diagnostics.codes(), [code::UNDECLARED, code::UNDECLARED]);
+
+        let summary: Vec<_> = symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.kind, s.scope.0, s.uses))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("x", SymbolKind::Var, 0, 1),
+                ("f", SymbolKind::Func, 0, 1),
+                ("a", SymbolKind::Param, 1, 1),
+                ("x", SymbolKind::Var, 1, 1),
+                ("i", SymbolKind::Var, 2, 3),
+                ("i", SymbolKind::Var, 3, 0),
+                ("a", SymbolKind::Var, 3, 0),
+                ("y", SymbolKind::Var, 4, 0),
+                ("y", SymbolKind::Var, 5, 0),
+                ("main", SymbolKind::Func, 0, 0),
+            ]
+        );
+        assert_eq!(
+            symbols.scope(symbols[SymbolId(4)].scope).kind,
+            ScopeKind::ForInit
+        );
+    }
+
+    #[test]
+    fn reports_redeclarations_in_the_same_scope_only() {
+        let (symbols, diagnostics) = check(
+            "int a[2];\nint f(int a, int a) { int a; return 0; }\nint main() { return f(a); }",
+        );
+        assert_eq!(
+            diagnostics.codes(),
+            [
+                code::DUPLICATE_DECLARATION,
+                code::DUPLICATE_DECLARATION,
+                code::CALL_MISMATCH
+            ]
+        );
+        let first = diagnostics.iter().next().unwrap();
+        assert_eq!(
+            (first.span.unwrap().line, first.labels[0].span.column),
+            (2, 11)
+        );
+        assert_eq!(symbols.iter().next().unwrap().kind, SymbolKind::Array);
+    }
+
+    /// The code, line and column of every diagnostic.
+    pub(super) fn located(diagnostics: &Diagnostics) -> Vec<(&'static str, usize, usize)> {
+        diagnostics
+            .iter()
+            .map(|d| {
+                let span = d.span.unwrap_or_default();
+                (d.code, span.line, span.column)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn types_every_expression_bottom_up() {
+        let source = "char c = 'a';\n\
+                      int a, b, m[2][3];\n\
+                      int main() {\n\
+                      \x20 int x = a < b + 1;\n\
+                      \x20 bool ok = c == 'b' && !(a == b) || c < 'z';\n\
+                      \x20 bool bad = 1 + true;\n\
+                      \x20 m[1][2] = c;\n\
+                      \x20 m[1] = 2;\n\
+                      \x20 if (a) { print(m[0], \"s\", m[true][1]); }\n\
+                      \x20 return a[0] + (c == 1);\n\
+                      }";
+        let checked = crate::check_with(source, Lints::allow_all());
+        assert_eq!(
+            located(&checked.diagnostics),
+            [
+                (code::TYPE_MISMATCH, 4, 11),
+                (code::TYPE_MISMATCH, 6, 18),
+                (code::TYPE_MISMATCH, 6, 14),
+                (code::TYPE_MISMATCH, 7, 13),
+                (code::TYPE_MISMATCH, 8, 3),
+                (code::TYPE_MISMATCH, 9, 7),
+                (code::TYPE_MISMATCH, 9, 18),
+                (code::TYPE_MISMATCH, 9, 31),
+                (code::TYPE_MISMATCH, 10, 10),
+                (code::TYPE_MISMATCH, 10, 23),
+                (code::TYPE_MISMATCH, 10, 18),
+            ]
+        );
+        let messages: Vec<_> = checked.diagnostics.iter().map(|d| &d.message).collect();
+        assert_eq!(messages[0], "expected `int`, found `bool`");
+        assert_eq!(messages[2], "expected `bool`, found `int`");
+        assert_eq!(messages[4], "cannot assign to `m[1]` of type `int[3]`");
+        assert_eq!(messages[6], "cannot print a value of type `int[3]`");
+
+        let type_at = |text: &str| {
+            let start = source.find(text).unwrap();
+            let span = checked
+                .types
+                .keys()
+                .find(|span| span.start == start && span.end == start + text.len())
+                .unwrap();
+            checked.types[span].to_string()
+        };
+        assert_eq!(type_at("b + 1"), "int");
+        assert_eq!(type_at("a < b + 1"), "bool");
+        assert_eq!(type_at("m[1][2]"), "int");
+        assert_eq!(type_at("m[1]"), "int[3]");
+        let main = checked.symbols.iter().find(|s| s.name == "main").unwrap();
+        assert_eq!(main.ty.to_string(), "int()");
+    }
+
+    #[test]
+    fn checks_calls_by_arity_argument_types_and_return_type() {
+        let source = "bool even(int n, char c) { return n % 2 == 0; }\n\
+                      int main() {\n\
+                      \x20 int x = 1;\n\
+                      \x20 bool b = even(x * 2 + 1, 'c') && !even(x, 'd');\n\
+                      \x20 int y = even(1, 'a');\n\
+                      \x20 even(true, 1);\n\
+                      \x20 even(x);\n\
+                      \x20 x(1);\n\
+                      \x20 return nope(x);\n\
+                      }";
+        let checked = crate::check_with(source, Lints::allow_all());
+        assert_eq!(
+            located(&checked.diagnostics),
+            [
+                (code::TYPE_MISMATCH, 5, 11),
+                (code::TYPE_MISMATCH, 6, 8),
+                (code::TYPE_MISMATCH, 6, 14),
+                (code::CALL_MISMATCH, 7, 3),
+                (code::CALL_MISMATCH, 8, 3),
+                (code::UNDECLARED, 9, 10),
+            ]
+        );
+        let messages: Vec<_> = checked.diagnostics.iter().map(|d| &d.message).collect();
+        assert_eq!(messages[3], "`even` takes 2 arguments but 1 was given");
+        let labels = &checked.diagnostics.iter().nth(2).unwrap().labels;
+        assert_eq!(labels[0].message, "parameter 2 of `even` is `char`");
+        assert_eq!(labels[0].span.line, 1);
+    }
+
+    #[test]
+    fn rejects_initializers_for_arrays() {
+        let checked = crate::check_with(
+            "char s[3] = \"ab\";\n\
+             int main() {\n\
+             \x20 int a[2];\n\
+             \x20 int b[2] = a;\n\
+             \x20 return 0;\n\
+             }",
+            Lints::allow_all(),
+        );
+        assert_eq!(
+            located(&checked.diagnostics),
+            [(code::TYPE_MISMATCH, 1, 13), (code::TYPE_MISMATCH, 4, 14)]
+        );
+        let first = checked.diagnostics.iter().next().unwrap();
+        assert_eq!(first.message, "cannot initialize `s` of type `char[3]`");
+        assert_eq!(first.labels[0].span.column, 6);
+    }
+
+    #[test]
+    fn resolves_break_and_continue_to_the_innermost_loop() {
+        let source = "int main() {\n\
+                      \x20 break;\n\
+                      \x20 for (int i = 0; i < 3; i = i + 1) {\n\
+                      \x20   for (;;) { break; }\n\
+                      \x20   if (i == 1) { continue; }\n\
+                      \x20 }\n\
+                      \x20 if (true) { continue; }\n\
+                      \x20 return 0;\n\
+                      }";
+        let checked = crate::check_with(source, Lints::allow_all());
+        assert_eq!(
+            located(&checked.diagnostics),
+            [
+                (code::LOOP_CONTROL_OUTSIDE_LOOP, 2, 3),
+                (code::LOOP_CONTROL_OUTSIDE_LOOP, 7, 15),
+            ]
+        );
+        assert_eq!(
+            checked.diagnostics.iter().next().unwrap().message,
+            "`break` outside of a loop"
+        );
+
+        let target_of = |text: &str| {
+            // After the `{ ` in front of the `break` or `continue`.
+            let start = source.find(text).unwrap() + 2;
+            let (_, target) = checked
+                .loop_targets
+                .iter()
+                .find(|(span, _)| span.start == start)
+                .unwrap();
+            &source[target.start..target.end]
+        };
+        assert!(target_of("{ break;").starts_with("for (;;)"));
+        assert!(target_of("{ continue;").starts_with("for (int i"));
+        assert_eq!(checked.loop_targets.len(), 2);
+    }
+
+    #[test]
+    fn evaluates_array_sizes_and_constant_arithmetic() {
+        let source = "int n = 2;\n\
+                      char m[2 * 3 + 0x1][0x2], z[1 - 2];\n\
+                      int a[n], b[true];\n\
+                      int main() {\n\
+                      \x20 int x = n / (3 - 3) + n % 0 + n / (1 - 2);\n\
+                      \x20 int y = 2147483647 + 1 - 1;\n\
+                      \x20 bool c = 1 / 0 == 0;\n\
+                      \x20 return 0;\n\
+                      }";
+        let checked = crate::check_with(source, Lints::allow_all());
+        assert_eq!(
+            located(&checked.diagnostics),
+            [
+                (code::INVALID_ARRAY_SIZE, 2, 29),
+                (code::INVALID_ARRAY_SIZE, 3, 7),
+                (code::TYPE_MISMATCH, 3, 13),
+                (code::CONSTANT_DIVISION_BY_ZERO, 5, 11),
+                (code::CONSTANT_DIVISION_BY_ZERO, 5, 25),
+                (code::CONSTANT_OVERFLOW, 6, 11),
+                (code::CONSTANT_DIVISION_BY_ZERO, 7, 12),
+            ]
+        );
+        let m = checked.symbols.iter().nth(1).unwrap();
+        assert_eq!(m.ty.to_string(), "char[7][2]");
+        let errors: Vec<_> = checked.diagnostics.iter().collect();
+        assert_eq!(errors[0].notes, ["`(1 - 2)` is `-1`"]);
+        assert_eq!(errors[3].labels[0].message, "`(3 - 3)` is always `0`");
+        assert_eq!(errors[5].message, "`2147483647 + 1` overflows `int`");
+    }
+
+    #[test]
+    fn takes_variables_that_are_never_assigned_as_constants() {
+        let source = "int g, h = 2147483647;\n\
+                      int main() {\n\
+                      \x20 int z = 0, y = z + 1, w = 0;\n\
+                      \x20 int x = 5 / z + 1 % g + h * 2 + 5 / y;\n\
+                      \x20 w = 1;\n\
+                      \x20 return x / (w - 1);\n\
+                      }";
+        let checked = crate::check_with(source, Lints::allow_all());
+        assert_eq!(
+            located(&checked.diagnostics),
+            [
+                (code::CONSTANT_DIVISION_BY_ZERO, 4, 11),
+                (code::CONSTANT_DIVISION_BY_ZERO, 4, 19),
+                (code::CONSTANT_OVERFLOW, 4, 27),
+            ]
+        );
+        let errors: Vec<_> = checked.diagnostics.iter().collect();
+        assert_eq!(errors[0].labels[0].message, "`z` is always `0`");
+        assert_eq!(errors[2].message, "`2147483647 * 2` overflows `int`");
+    }
+}
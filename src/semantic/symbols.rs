@@ -0,0 +1,281 @@
+//! Scopes and the symbols declared in them.
+
+use std::{collections::HashMap, fmt, ops::Index};
+
+use crate::token::Span;
+
+use super::types::Type;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ScopeId(pub usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SymbolId(pub usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeKind {
+    /// Functions and global variables.
+    Global,
+    /// The parameters and the outermost block of a function body, so a
+    /// parameter cannot be declared again in the body.
+    Function,
+    /// The block of an `if`, an `else` or a loop body.
+    Block,
+    /// The declarations in the header of a `for`, enclosing its body.
+    ForInit,
+}
+
+#[derive(Debug, Clone)]
+pub struct Scope {
+    pub kind: ScopeKind,
+    /// `None` only for the global scope.
+    pub parent: Option<ScopeId>,
+    pub span: Span,
+    /// The symbols declared in the scope, in declaration order.
+    pub symbols: Vec<SymbolId>,
+    names: HashMap<String, SymbolId>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Var,
+    Param,
+    Func,
+    Array,
+}
+
+impl fmt::Display for SymbolKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SymbolKind::Var => "var",
+            SymbolKind::Param => "param",
+            SymbolKind::Func => "func",
+            SymbolKind::Array => "array",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// A [`Type::Function`] for functions, a [`Type::Array`] for arrays.
+    pub ty: Type,
+    pub scope: ScopeId,
+    /// Where the name was declared.
+    pub span: Span,
+    /// How many times the name was referred to after its declaration.
+    pub uses: usize,
+    /// How many of the uses assign to it, or to one of its elements.
+    pub writes: usize,
+}
+
+/// Every scope of a program and the symbols declared in them. While
+/// checking, the scopes that are entered and not yet left form a stack that
+/// names are resolved against, from the innermost scope outwards.
+#[derive(Debug, Clone)]
+pub struct SymbolTable {
+    scopes: Vec<Scope>,
+    symbols: Vec<Symbol>,
+    /// The innermost open scope.
+    current: ScopeId,
+    /// The symbol each resolved or declared name refers to, by the span of
+    /// the name.
+    references: HashMap<Span, SymbolId>,
+}
+
+impl Default for SymbolTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SymbolTable {
+    /// A table with only the global scope, which is open.
+    pub fn new() -> Self {
+        Self {
+            scopes: vec![Scope {
+                kind: ScopeKind::Global,
+                parent: None,
+                span: Span::default(),
+                symbols: vec![],
+                names: HashMap::new(),
+            }],
+            symbols: vec![],
+            current: ScopeId(0),
+            references: HashMap::new(),
+        }
+    }
+
+    pub fn global(&self) -> ScopeId {
+        ScopeId(0)
+    }
+
+    pub fn current(&self) -> ScopeId {
+        self.current
+    }
+
+    /// Opens a scope inside the current one.
+    pub fn push_scope(&mut self, kind: ScopeKind, span: Span) -> ScopeId {
+        let id = ScopeId(self.scopes.len());
+        self.scopes.push(Scope {
+            kind,
+            parent: Some(self.current),
+            span,
+            symbols: vec![],
+            names: HashMap::new(),
+        });
+        self.current = id;
+        id
+    }
+
+    /// Closes the current scope, its names can no longer be resolved.
+    pub fn pop_scope(&mut self) {
+        self.current = self.scopes[self.current.0]
+            .parent
+            .expect("the global scope is never closed");
+    }
+
+    /// Declares `name` in the current scope. Fails with the symbol already
+    /// declared under that name there. Names of enclosing scopes are
+    /// shadowed.
+    pub fn declare(
+        &mut self,
+        name: &str,
+        kind: SymbolKind,
+        ty: Type,
+        span: Span,
+    ) -> Result<SymbolId, SymbolId> {
+        let scope = &mut self.scopes[self.current.0];
+        if let Some(&existing) = scope.names.get(name) {
+            return Err(existing);
+        }
+        let id = SymbolId(self.symbols.len());
+        scope.names.insert(name.to_owned(), id);
+        scope.symbols.push(id);
+        self.symbols.push(Symbol {
+            name: name.to_owned(),
+            kind,
+            ty,
+            scope: self.current,
+            span,
+            uses: 0,
+            writes: 0,
+        });
+        self.references.insert(span, id);
+        Ok(id)
+    }
+
+    /// The symbol `name` refers to in the current scope, without counting it
+    /// as a use.
+    pub fn lookup(&self, name: &str) -> Option<SymbolId> {
+        let mut scope = Some(self.current);
+        while let Some(id) = scope {
+            let scope_ref = &self.scopes[id.0];
+            if let Some(&symbol) = scope_ref.names.get(name) {
+                return Some(symbol);
+            }
+            scope = scope_ref.parent;
+        }
+        None
+    }
+
+    /// Looks `name` up, and when it is declared records the reference at
+    /// `span` and counts it as a use.
+    pub fn resolve(&mut self, name: &str, span: Span) -> Option<SymbolId> {
+        let symbol = self.lookup(name)?;
+        self.symbols[symbol.0].uses += 1;
+        self.references.insert(span, symbol);
+        Some(symbol)
+    }
+
+    /// Counts the use of the name resolved at `span` as a write.
+    pub fn record_write(&mut self, span: Span) {
+        if let Some(symbol) = self.reference(span) {
+            self.symbols[symbol.0].writes += 1;
+        }
+    }
+
+    /// The symbol the name at `span` was resolved to, or declares.
+    pub fn reference(&self, span: Span) -> Option<SymbolId> {
+        self.references.get(&span).copied()
+    }
+
+    pub fn scope(&self, id: ScopeId) -> &Scope {
+        &self.scopes[id.0]
+    }
+
+    pub fn scopes(&self) -> &[Scope] {
+        &self.scopes
+    }
+
+    /// Every symbol, in declaration order.
+    pub fn iter(&self) -> std::slice::Iter<'_, Symbol> {
+        self.symbols.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+}
+
+impl Index<SymbolId> for SymbolTable {
+    type Output = Symbol;
+
+    fn index(&self, id: SymbolId) -> &Symbol {
+        &self.symbols[id.0]
+    }
+}
+
+impl<'a> IntoIterator for &'a SymbolTable {
+    type Item = &'a Symbol;
+    type IntoIter = std::slice::Iter<'a, Symbol>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn declare(table: &mut SymbolTable, name: &str) -> Result<SymbolId, SymbolId> {
+        table.declare(name, SymbolKind::Var, Type::Int, Span::default())
+    }
+
+    #[test]
+    fn resolves_innermost_first_and_forgets_closed_scopes() {
+        let mut table = SymbolTable::new();
+        let global = declare(&mut table, "x").unwrap();
+        table.push_scope(ScopeKind::Function, Span::default());
+        let param = declare(&mut table, "y").unwrap();
+        assert_eq!(declare(&mut table, "y"), Err(param));
+
+        let block = table.push_scope(ScopeKind::Block, Span::default());
+        let shadow = declare(&mut table, "x").unwrap();
+        assert_eq!(table.lookup("x"), Some(shadow));
+        assert_eq!(table.lookup("y"), Some(param));
+        assert_eq!(table[shadow].scope, block);
+
+        table.pop_scope();
+        let span = Span {
+            start: 4,
+            end: 5,
+            line: 1,
+            column: 5,
+        };
+        assert_eq!(table.resolve("x", span), Some(global));
+        assert_eq!(table.reference(span), Some(global));
+        assert_eq!(table[global].uses, 1);
+        assert_eq!(table[shadow].uses, 0);
+
+        table.pop_scope();
+        assert_eq!(table.lookup("y"), None);
+        assert_eq!(table.current(), table.global());
+    }
+}
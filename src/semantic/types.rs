@@ -0,0 +1,88 @@
+//! The types the checker assigns to symbols and expressions.
+
+use std::fmt;
+
+use crate::ast::{Literal, TypeName};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Type {
+    Int,
+    Bool,
+    Char,
+    /// String literals, which can only be printed.
+    String,
+    /// `int a[2][3]` is an array of 2 arrays of 3 ints.
+    Array(Box<Type>, usize),
+    Function {
+        params: Vec<Type>,
+        ret: Box<Type>,
+    },
+    /// The type of something that has no value.
+    Void,
+    /// The type of an expression that already has an error reported, it
+    /// matches every other type so the error is not reported again.
+    Error,
+}
+
+impl Type {
+    pub fn of_literal(literal: &Literal) -> Type {
+        match literal {
+            Literal::Int(_) => Type::Int,
+            Literal::Bool(_) => Type::Bool,
+            Literal::Char(_) => Type::Char,
+            Literal::String(_) => Type::String,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        *self == Type::Error
+    }
+
+    /// `int`, `bool` and `char`, the types variables and values can have.
+    pub fn is_scalar(&self) -> bool {
+        matches!(self, Type::Int | Type::Bool | Type::Char)
+    }
+
+    /// Whether a value of this type fits where `other` is expected, which is
+    /// always the case when either is [`Type::Error`].
+    pub fn matches(&self, other: &Type) -> bool {
+        self.is_error() || other.is_error() || self == other
+    }
+}
+
+impl From<TypeName> for Type {
+    fn from(name: TypeName) -> Type {
+        match name {
+            TypeName::Int => Type::Int,
+            TypeName::Bool => Type::Bool,
+            TypeName::Char => Type::Char,
+        }
+    }
+}
+
+/// C-like: `int`, `char[2][3]`, `bool(int, char)` for a function.
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Int => f.write_str("int"),
+            Type::Bool => f.write_str("bool"),
+            Type::Char => f.write_str("char"),
+            Type::String => f.write_str("string"),
+            Type::Array(..) => {
+                let mut element = self;
+                let mut dims = String::new();
+                while let Type::Array(inner, len) = element {
+                    dims.push_str(&format!("[{len}]"));
+                    element = inner;
+                }
+                write!(f, "{element}{dims}")
+            }
+            Type::Function { params, ret } => {
+                let params: Vec<String> = params.iter().map(ToString::to_string).collect();
+                write!(f, "{ret}({})", params.join(", "))
+            }
+            Type::Void => f.write_str("void"),
+            Type::Error => f.write_str("{error}"),
+        }
+    }
+}
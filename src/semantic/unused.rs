@@ -0,0 +1,195 @@
+//! Warnings for declarations that are never used: local variables and
+//! parameters that are never read, and functions that neither `main` nor
+//! the initializers of globals call.
+//! Names starting with `_` are meant to be unused and are not reported.
+
+use std::collections::HashSet;
+
+use crate::diagnostic::{Diagnostics, Lint, Lints};
+
+use super::{
+    symbols::{SymbolId, SymbolKind, SymbolTable},
+    Calls,
+};
+
+/// Reports the unused symbols in `symbols`, in declaration order, at the
+/// levels in `lints`. `calls` are the functions each function calls.
+pub fn check(symbols: &SymbolTable, calls: &Calls, lints: &Lints, diagnostics: &mut Diagnostics) {
+    let called = called_from_main(symbols, calls);
+    for (i, symbol) in symbols.iter().enumerate() {
+        if symbol.name.starts_with('_') {
+            continue;
+        }
+        let name = &symbol.name;
+        let local = symbol.scope != symbols.global();
+        let (lint, message) = match symbol.kind {
+            SymbolKind::Func => match &called {
+                Some(called) if !called.contains(&SymbolId(i)) && name != "main" => (
+                    Lint::UnusedFunctions,
+                    format!("function `{name}` is never called from `main`"),
+                ),
+                _ => continue,
+            },
+            SymbolKind::Param if symbol.uses == 0 => {
+                (Lint::UnusedParameters, format!("unused parameter `{name}`"))
+            }
+            SymbolKind::Var | SymbolKind::Array if local && symbol.uses == 0 => {
+                (Lint::UnusedVariables, format!("unused variable `{name}`"))
+            }
+            SymbolKind::Var | SymbolKind::Array | SymbolKind::Param
+                if (local || symbol.kind == SymbolKind::Param)
+                    && symbol.uses > 0
+                    && symbol.uses == symbol.writes =>
+            {
+                (
+                    Lint::UnusedAssignments,
+                    format!("`{name}` is assigned but never read"),
+                )
+            }
+            _ => continue,
+        };
+        if let Some(diagnostic) = lints.diagnostic(lint, message) {
+            let diagnostic = diagnostic.with_span(symbol.span);
+            diagnostics.push(match lint {
+                Lint::UnusedFunctions => diagnostic,
+                _ => diagnostic.with_note(format!(
+                    "if this is intentional, prefix it with an underscore: `_{name}`"
+                )),
+            });
+        }
+    }
+}
+
+/// The functions `main` and the initializers of globals call, directly or
+/// through other functions, or `None` when there is no `main`.
+fn called_from_main(symbols: &SymbolTable, calls: &Calls) -> Option<HashSet<SymbolId>> {
+    let main = symbols
+        .scope(symbols.global())
+        .symbols
+        .iter()
+        .copied()
+        .find(|&id| symbols[id].name == "main" && symbols[id].kind == SymbolKind::Func)?;
+    let mut called = HashSet::new();
+    let mut pending = vec![main];
+    for &callee in calls.get(&None).into_iter().flatten() {
+        if called.insert(callee) {
+            pending.push(callee);
+        }
+    }
+    while let Some(function) = pending.pop() {
+        for &callee in calls.get(&Some(function)).into_iter().flatten() {
+            if called.insert(callee) {
+                pending.push(callee);
+            }
+        }
+    }
+    Some(called)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diagnostic::{code, Level, Lint, Lints, Severity};
+
+    const SOURCE: &str = "int g;\n\
+                          int helper(int used, int unused, int idle, int _ignored) {\n\
+                          \x20 int x = 1, y, z[2], _w;\n\
+                          \x20 int v;\n\
+                          \x20 y = 2;\n\
+                          \x20 z[0] = used;\n\
+                          \x20 unused = 3;\n\
+                          \x20 return x;\n\
+                          }\n\
+                          int dead(int n) { return dead(n); }\n\
+                          int _spare() { return 0; }\n\
+                          int main() { return helper(g, 0, 0, 0); }";
+
+    fn check(source: &str, lints: Lints) -> Vec<(&'static str, Severity, String)> {
+        let mut sem = crate::semantic::Sem::with_lints(lints);
+        let mut diagnostics = crate::diagnostic::Diagnostics::new();
+        let program = crate::lower(source).program.unwrap();
+        sem.check(&program, &mut diagnostics);
+        diagnostics
+            .iter()
+            .map(|d| (d.code, d.severity, d.message.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn warns_about_unused_declarations() {
+        let warning = |code, message: &str| (code, Severity::Warning, message.to_owned());
+        assert_eq!(
+            check(SOURCE, Lints::default()),
+            [
+                warning(
+                    code::UNUSED_ASSIGNMENT,
+                    "`unused` is assigned but never read"
+                ),
+                warning(code::UNUSED_VARIABLE, "unused parameter `idle`"),
+                warning(code::UNUSED_ASSIGNMENT, "`y` is assigned but never read"),
+                warning(code::UNUSED_ASSIGNMENT, "`z` is assigned but never read"),
+                warning(code::UNUSED_VARIABLE, "unused variable `v`"),
+                warning(
+                    code::UNUSED_FUNCTION,
+                    "function `dead` is never called from `main`"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn counts_calls_from_globals_and_calls_before_the_declaration() {
+        let messages = |source| {
+            check(source, Lints::default())
+                .into_iter()
+                .map(|(_, _, message)| message)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            messages("int f() { return 3; }\nint g = f();\nint main() { return g; }"),
+            Vec::<String>::new()
+        );
+        // `h` is only reported as not declared, and `k` is called through it.
+        assert_eq!(
+            messages(
+                "int main() { return h(); }\n\
+                 int k() { return 1; }\n\
+                 int h() { return k(); }"
+            ),
+            ["`h` is not declared"]
+        );
+    }
+
+    #[test]
+    fn does_not_count_assignments_that_are_type_errors_as_writes() {
+        let messages = check(
+            "int main() {\n\
+             \x20   int a[2], b[2];\n\
+             \x20   a = b;\n\
+             \x20   return b[0];\n\
+             }",
+            Lints::default(),
+        );
+        assert_eq!(
+            messages
+                .iter()
+                .map(|(_, _, message)| message.as_str())
+                .collect::<Vec<_>>(),
+            ["cannot assign to `a` of type `int[2]`"]
+        );
+    }
+
+    #[test]
+    fn follows_the_lint_levels() {
+        let mut lints = Lints::default();
+        lints.set_by_name("unused", Level::Allow).unwrap();
+        lints.set(Lint::UnusedFunctions, Level::Deny);
+        assert_eq!(
+            check(SOURCE, lints),
+            [(
+                code::UNUSED_FUNCTION,
+                Severity::Error,
+                "function `dead` is never called from `main`".to_owned()
+            )]
+        );
+    }
+}
@@ -0,0 +1,622 @@
+//! The `grammer.g` notation and the FIRST/FOLLOW computations the LL(1)
+//! parsing table is built from.
+//!
+//! Every non-empty line is a production `Lhs -> Symbol Symbol ...`, where
+//! `''` stands for the empty string and `#` starts a comment. A symbol is a
+//! non-terminal when it is the left side of some production, otherwise it is
+//! a terminal named after its `TokenType`. The left side of the first
+//! production is the start symbol and `$` is the end of input.
+//!
+//! `build.rs` compiles this file too, so it only depends on `std`.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Write},
+};
+
+/// The empty string.
+pub const EPSILON: &str = "''";
+/// The end of input, `TokenType::End`.
+pub const END: &str = "$";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Production {
+    pub lhs: String,
+    /// Empty for `''`.
+    pub rhs: Vec<String>,
+    /// The 1-based line of the production in the grammar file.
+    pub line: usize,
+}
+
+impl fmt::Display for Production {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ->", self.lhs)?;
+        if self.rhs.is_empty() {
+            return write!(f, " {EPSILON}");
+        }
+        for symbol in &self.rhs {
+            write!(f, " {symbol}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrammarError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for GrammarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Grammar {
+    pub productions: Vec<Production>,
+    /// In order of their first production, the start symbol first.
+    pub non_terminals: Vec<String>,
+}
+
+/// The productions to expand for each `(non-terminal, terminal)` pair, by
+/// index into [`Grammar::productions`]. More than one is a conflict.
+pub type PredictTable = BTreeMap<(String, String), Vec<usize>>;
+
+impl Grammar {
+    pub fn parse(text: &str) -> Result<Self, GrammarError> {
+        let mut productions = vec![];
+        let mut non_terminals: Vec<String> = vec![];
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let error = |message: &str| GrammarError {
+                line: line_number,
+                message: message.to_owned(),
+            };
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (lhs, rhs) = line.split_once("->").ok_or(error("expected `->`"))?;
+            let lhs = lhs.trim();
+            if lhs.is_empty() || lhs.contains(char::is_whitespace) {
+                return Err(error("expected a single non-terminal before `->`"));
+            }
+            if lhs == EPSILON || lhs == END {
+                return Err(error(&format!("`{lhs}` cannot be a non-terminal")));
+            }
+
+            let mut rhs: Vec<String> = rhs.split_whitespace().map(str::to_owned).collect();
+            if rhs.is_empty() {
+                return Err(error("expected symbols or `''` after `->`"));
+            }
+            if rhs.iter().any(|symbol| symbol == EPSILON) {
+                if rhs.len() > 1 {
+                    return Err(error("`''` must be the only symbol of its production"));
+                }
+                rhs.clear();
+            }
+            if rhs.iter().any(|symbol| symbol == END) {
+                return Err(error("`$` cannot appear in a production"));
+            }
+
+            if !non_terminals.iter().any(|non_terminal| non_terminal == lhs) {
+                non_terminals.push(lhs.to_owned());
+            }
+            productions.push(Production {
+                lhs: lhs.to_owned(),
+                rhs,
+                line: line_number,
+            });
+        }
+
+        if productions.is_empty() {
+            return Err(GrammarError {
+                line: 1,
+                message: "the grammar has no productions".to_owned(),
+            });
+        }
+        Ok(Self {
+            productions,
+            non_terminals,
+        })
+    }
+
+    pub fn start(&self) -> &str {
+        &self.non_terminals[0]
+    }
+
+    pub fn is_non_terminal(&self, symbol: &str) -> bool {
+        self.non_terminals
+            .iter()
+            .any(|non_terminal| non_terminal == symbol)
+    }
+
+    /// The terminals in order of first use, followed by `$`.
+    pub fn terminals(&self) -> Vec<&str> {
+        let mut terminals: Vec<&str> = vec![];
+        for symbol in self.productions.iter().flat_map(|p| &p.rhs) {
+            if !self.is_non_terminal(symbol) && !terminals.contains(&symbol.as_str()) {
+                terminals.push(symbol);
+            }
+        }
+        terminals.push(END);
+        terminals
+    }
+
+    /// The productions of `non_terminal` with their indices.
+    pub fn productions_of<'a>(
+        &'a self,
+        non_terminal: &'a str,
+    ) -> impl Iterator<Item = (usize, &'a Production)> + 'a {
+        self.productions
+            .iter()
+            .enumerate()
+            .filter(move |(_, production)| production.lhs == non_terminal)
+    }
+
+    pub fn analyze(&self) -> Analysis {
+        let mut analysis = Analysis {
+            nullable: BTreeSet::new(),
+            first: self
+                .non_terminals
+                .iter()
+                .map(|non_terminal| (non_terminal.clone(), BTreeSet::new()))
+                .collect(),
+            follow: self
+                .non_terminals
+                .iter()
+                .map(|non_terminal| (non_terminal.clone(), BTreeSet::new()))
+                .collect(),
+        };
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for production in &self.productions {
+                let first = analysis.first_of(self, &production.rhs);
+                if first.contains(EPSILON) {
+                    changed |= analysis.nullable.insert(production.lhs.clone());
+                }
+                let set = analysis.first.get_mut(&production.lhs).unwrap();
+                for terminal in first {
+                    changed |= set.insert(terminal);
+                }
+            }
+        }
+
+        analysis
+            .follow
+            .get_mut(self.start())
+            .unwrap()
+            .insert(END.to_owned());
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for production in &self.productions {
+                for (i, symbol) in production.rhs.iter().enumerate() {
+                    if !self.is_non_terminal(symbol) {
+                        continue;
+                    }
+                    let mut follow = analysis.first_of(self, &production.rhs[i + 1..]);
+                    if follow.remove(EPSILON) {
+                        follow.extend(analysis.follow[&production.lhs].iter().cloned());
+                    }
+                    let set = analysis.follow.get_mut(symbol).unwrap();
+                    for terminal in follow {
+                        changed |= set.insert(terminal);
+                    }
+                }
+            }
+        }
+        analysis
+    }
+
+    /// Puts each production under the terminals in its FIRST set, and under
+    /// the FOLLOW set of its left side when it can derive `''`.
+    pub fn predict_table(&self, analysis: &Analysis) -> PredictTable {
+        let mut table = PredictTable::new();
+        for (index, production) in self.productions.iter().enumerate() {
+            let mut predict = analysis.first_of(self, &production.rhs);
+            if predict.remove(EPSILON) {
+                predict.extend(analysis.follow[&production.lhs].iter().cloned());
+            }
+            for terminal in predict {
+                table
+                    .entry((production.lhs.clone(), terminal))
+                    .or_default()
+                    .push(index);
+            }
+        }
+        table
+    }
+
+    /// Every cell of the predict table with more than one production.
+    pub fn conflicts(&self, analysis: &Analysis) -> Vec<Conflict> {
+        self.predict_table(analysis)
+            .into_iter()
+            .filter(|(_, productions)| productions.len() > 1)
+            .map(|((non_terminal, terminal), productions)| {
+                // A production predicted through FOLLOW is one that derives `''`.
+                let through_follow = productions.iter().any(|&index| {
+                    !analysis
+                        .first_of(self, &self.productions[index].rhs)
+                        .contains(&terminal)
+                });
+                Conflict {
+                    non_terminal,
+                    terminal,
+                    kind: if through_follow {
+                        ConflictKind::FirstFollow
+                    } else {
+                        ConflictKind::FirstFirst
+                    },
+                    productions,
+                }
+            })
+            .collect()
+    }
+
+    /// The non-terminals no derivation from the start symbol reaches.
+    pub fn unreachable(&self) -> Vec<&str> {
+        let mut reached = vec![self.start()];
+        let mut i = 0;
+        while let Some(&non_terminal) = reached.get(i) {
+            for (_, production) in self.productions_of(non_terminal) {
+                for symbol in &production.rhs {
+                    if self.is_non_terminal(symbol) && !reached.contains(&symbol.as_str()) {
+                        reached.push(symbol);
+                    }
+                }
+            }
+            i += 1;
+        }
+        self.non_terminals
+            .iter()
+            .map(String::as_str)
+            .filter(|non_terminal| !reached.contains(non_terminal))
+            .collect()
+    }
+
+    /// The non-terminals that never derive a string of terminals.
+    pub fn unproductive(&self) -> Vec<&str> {
+        let mut productive: BTreeSet<&str> = BTreeSet::new();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for production in &self.productions {
+                if !productive.contains(production.lhs.as_str())
+                    && production.rhs.iter().all(|symbol| {
+                        !self.is_non_terminal(symbol) || productive.contains(symbol.as_str())
+                    })
+                {
+                    productive.insert(&production.lhs);
+                    changed = true;
+                }
+            }
+        }
+        self.non_terminals
+            .iter()
+            .map(String::as_str)
+            .filter(|non_terminal| !productive.contains(non_terminal))
+            .collect()
+    }
+
+    /// Each cycle `A => B ... => A` of leftmost non-terminals, as the
+    /// productions it goes through. A cycle is reported once, starting at its
+    /// non-terminal that comes first in the grammar.
+    pub fn left_recursion(&self, analysis: &Analysis) -> Vec<Vec<usize>> {
+        // `A -> B` when B can be the first symbol A derives.
+        let mut edges: Vec<(usize, &str)> = vec![];
+        for (index, production) in self.productions.iter().enumerate() {
+            for symbol in &production.rhs {
+                if !self.is_non_terminal(symbol) {
+                    break;
+                }
+                edges.push((index, symbol));
+                if !analysis.nullable.contains(symbol) {
+                    break;
+                }
+            }
+        }
+
+        let mut cycles: Vec<Vec<usize>> = vec![];
+        let mut seen: Vec<BTreeSet<&str>> = vec![];
+        for start in &self.non_terminals {
+            // Breadth first, so the shortest cycle through `start` is found.
+            let mut paths: Vec<(&str, Vec<usize>)> = vec![(start, vec![])];
+            let mut visited: BTreeSet<&str> = BTreeSet::new();
+            let mut i = 0;
+            let mut cycle = None;
+            while let Some((non_terminal, path)) = paths.get(i).cloned() {
+                i += 1;
+                for &(index, symbol) in &edges {
+                    if self.productions[index].lhs != non_terminal {
+                        continue;
+                    }
+                    let mut path = path.clone();
+                    path.push(index);
+                    if symbol == start {
+                        cycle = Some(path);
+                        break;
+                    }
+                    if visited.insert(symbol) {
+                        paths.push((symbol, path));
+                    }
+                }
+                if cycle.is_some() {
+                    break;
+                }
+            }
+
+            if let Some(cycle) = cycle {
+                let members: BTreeSet<&str> = cycle
+                    .iter()
+                    .map(|&index| self.productions[index].lhs.as_str())
+                    .collect();
+                if !seen.contains(&members) {
+                    seen.push(members);
+                    cycles.push(cycle);
+                }
+            }
+        }
+        cycles
+    }
+
+    /// The nullable non-terminals, the FIRST and FOLLOW sets and the predict
+    /// table as Markdown.
+    pub fn report(&self, analysis: &Analysis) -> String {
+        let terminals = self.terminals();
+        let ordered = |set: &BTreeSet<String>| {
+            let mut symbols: Vec<&str> = terminals
+                .iter()
+                .copied()
+                .filter(|terminal| set.contains(*terminal))
+                .collect();
+            if set.contains(EPSILON) {
+                symbols.push(EPSILON);
+            }
+            symbols.join(", ")
+        };
+
+        let mut out = String::new();
+        out.push_str("## nullable\n\n");
+        let nullable: Vec<&str> = self
+            .non_terminals
+            .iter()
+            .map(String::as_str)
+            .filter(|non_terminal| analysis.nullable.contains(*non_terminal))
+            .collect();
+        out.push_str(&nullable.join(", "));
+        out.push_str("\n\n## first\n\n| Non-Terminal | First Set |\n|---|---|\n");
+        for non_terminal in &self.non_terminals {
+            let _ = writeln!(
+                out,
+                "| {non_terminal} | {} |",
+                escape(&ordered(&analysis.first[non_terminal]))
+            );
+        }
+        out.push_str("\n## follow\n\n| Non-Terminal | Follow Set |\n|---|---|\n");
+        for non_terminal in &self.non_terminals {
+            let _ = writeln!(
+                out,
+                "| {non_terminal} | {} |",
+                escape(&ordered(&analysis.follow[non_terminal]))
+            );
+        }
+
+        out.push_str("\n## predict table\n\n| Non-Terminal |");
+        for terminal in &terminals {
+            let _ = write!(out, " {} |", escape(terminal));
+        }
+        out.push_str("\n|---|");
+        out.push_str(&"---|".repeat(terminals.len()));
+        out.push('\n');
+        let table = self.predict_table(analysis);
+        for non_terminal in &self.non_terminals {
+            let _ = write!(out, "| {non_terminal} |");
+            for terminal in &terminals {
+                let cell = table
+                    .get(&(non_terminal.clone(), terminal.to_string()))
+                    .map(|productions| {
+                        productions
+                            .iter()
+                            .map(|&index| escape(&self.productions[index].to_string()))
+                            .collect::<Vec<_>>()
+                            .join("<br>")
+                    })
+                    .unwrap_or_default();
+                let _ = write!(out, " {cell} |");
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn escape(cell: &str) -> String {
+    cell.replace('|', "\\|")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictKind {
+    /// Two productions start with the same terminal.
+    FirstFirst,
+    /// A production that derives `''` and another one both expect the
+    /// terminal, one of them through the FOLLOW set.
+    FirstFollow,
+}
+
+impl fmt::Display for ConflictKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ConflictKind::FirstFirst => "FIRST/FIRST",
+            ConflictKind::FirstFollow => "FIRST/FOLLOW",
+        })
+    }
+}
+
+/// A cell of the predict table with more than one production.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub non_terminal: String,
+    pub terminal: String,
+    pub kind: ConflictKind,
+    /// Indices into [`Grammar::productions`], in grammar order.
+    pub productions: Vec<usize>,
+}
+
+/// The nullable non-terminals and the FIRST and FOLLOW set of every
+/// non-terminal. FIRST sets contain `''` when the non-terminal is nullable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    pub nullable: BTreeSet<String>,
+    pub first: BTreeMap<String, BTreeSet<String>>,
+    pub follow: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Analysis {
+    /// The FIRST set of a sequence of symbols, with `''` when all of them are
+    /// nullable.
+    pub fn first_of(&self, grammar: &Grammar, symbols: &[String]) -> BTreeSet<String> {
+        let mut first = BTreeSet::new();
+        for symbol in symbols {
+            if !grammar.is_non_terminal(symbol) {
+                first.insert(symbol.clone());
+                return first;
+            }
+            first.extend(
+                self.first[symbol]
+                    .iter()
+                    .filter(|terminal| *terminal != EPSILON)
+                    .cloned(),
+            );
+            if !self.nullable.contains(symbol) {
+                return first;
+            }
+        }
+        first.insert(EPSILON.to_owned());
+        first
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(symbols: &[&str]) -> BTreeSet<String> {
+        symbols.iter().map(|s| s.to_string()).collect()
+    }
+
+    const EXPRESSIONS: &str = "\
+E -> T E'
+E' -> + T E'   # a comment
+E' -> ''
+T -> F T'
+T' -> * F T'
+T' -> ''
+F -> ( E )
+F -> id
+";
+
+    #[test]
+    fn computes_first_and_follow() {
+        let grammar = Grammar::parse(EXPRESSIONS).unwrap();
+        assert_eq!(grammar.start(), "E");
+        assert_eq!(grammar.terminals(), ["+", "*", "(", ")", "id", "$"]);
+        assert_eq!(grammar.productions[2].rhs, Vec::<String>::new());
+        assert_eq!(grammar.productions[2].to_string(), "E' -> ''");
+
+        let analysis = grammar.analyze();
+        assert_eq!(analysis.nullable, set(&["E'", "T'"]));
+        assert_eq!(analysis.first["E"], set(&["(", "id"]));
+        assert_eq!(analysis.first["T'"], set(&["*", "''"]));
+        assert_eq!(analysis.follow["E"], set(&[")", "$"]));
+        assert_eq!(analysis.follow["T"], set(&["+", ")", "$"]));
+        assert_eq!(analysis.follow["F"], set(&["*", "+", ")", "$"]));
+
+        let table = grammar.predict_table(&analysis);
+        assert_eq!(table[&("E'".to_owned(), ")".to_owned())], [2]);
+        assert_eq!(table[&("F".to_owned(), "id".to_owned())], [7]);
+        assert!(table.values().all(|productions| productions.len() == 1));
+    }
+
+    #[test]
+    fn classifies_conflicts() {
+        let grammar =
+            Grammar::parse("S -> A a\nA -> a\nA -> ''\nA -> a b\nS -> B\nB -> c\nB -> c d")
+                .unwrap();
+        let conflicts = grammar.conflicts(&grammar.analyze());
+        let found: Vec<_> = conflicts
+            .iter()
+            .map(|c| {
+                (
+                    c.non_terminal.as_str(),
+                    c.terminal.as_str(),
+                    c.kind,
+                    c.productions.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("A", "a", ConflictKind::FirstFollow, vec![1, 2, 3]),
+                ("B", "c", ConflictKind::FirstFirst, vec![5, 6]),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_unreachable_unproductive_and_left_recursive_symbols() {
+        let grammar =
+            Grammar::parse("S -> A b\nA -> B x\nA -> ''\nB -> A y\nLoop -> Loop z\nF -> f")
+                .unwrap();
+        let analysis = grammar.analyze();
+        assert_eq!(grammar.unreachable(), ["Loop", "F"]);
+        assert_eq!(grammar.unproductive(), ["Loop"]);
+        // A => B x => A y x, found once from A.
+        assert_eq!(grammar.left_recursion(&analysis), [vec![1, 3], vec![4]]);
+    }
+
+    #[test]
+    fn report_has_every_table() {
+        let grammar = Grammar::parse(EXPRESSIONS).unwrap();
+        let report = grammar.report(&grammar.analyze());
+        assert!(report.starts_with("## nullable\n\nE', T'\n"));
+        assert!(report.contains("| T' | *, '' |"));
+        assert!(report.contains("| F | +, *, ), $ |"));
+        assert!(report.contains(
+            "| Non-Terminal | + | * | ( | ) | id | $ |\n|---|---|---|---|---|---|---|\n"
+        ));
+        assert!(report.contains("| E' | E' -> + T E' |  |  | E' -> '' |  | E' -> '' |"));
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        let error = |text| Grammar::parse(text).unwrap_err();
+        assert_eq!(error("A -> a\nA b").line, 2);
+        assert_eq!(error("A B -> a").line, 1);
+        assert_eq!(error("A -> a '' b").line, 1);
+        assert_eq!(error("A ->").line, 1);
+        assert_eq!(error("A -> $").line, 1);
+        assert_eq!(error("# nothing").line, 1);
+    }
+
+    #[test]
+    fn grammer_g_is_ll1() {
+        let grammar = Grammar::parse(super::super::GRAMMAR).unwrap();
+        let table = grammar.predict_table(&grammar.analyze());
+        for ((non_terminal, terminal), productions) in &table {
+            assert_eq!(
+                productions.len(),
+                1,
+                "{non_terminal} on {terminal}: {productions:?}"
+            );
+        }
+        assert_eq!(table.len(), super::super::add_rules().len());
+    }
+}
@@ -1,11 +1,31 @@
-use slab_tree::{Tree, TreeBuilder};
+use slab_tree::{NodeId, Tree, TreeBuilder};
 
-use crate::token::Token;
+use crate::diagnostic::{code, Diagnostic, Diagnostics};
+use crate::token::{Span, Token};
 use crate::{lexial::Lexer, token::TokenType};
 
-use super::{NonTerminal, ParsingTable, Symbol, SymbolTree};
+use super::{follow_set, NonTerminal, ParsingTable, Symbol, SymbolTree};
 
-pub(crate) struct Parser {
+/// Tokens that end or start a statement or a block. Error recovery stops at
+/// them when the rest of the stack can continue there.
+const SYNC_TOKENS: [TokenType; 13] = [
+    TokenType::T_Semicolon,
+    TokenType::T_LC,
+    TokenType::T_RC,
+    TokenType::T_If,
+    TokenType::T_Else,
+    TokenType::T_For,
+    TokenType::T_Print,
+    TokenType::T_Return,
+    TokenType::T_Break,
+    TokenType::T_Continue,
+    TokenType::T_Int,
+    TokenType::T_Bool,
+    TokenType::T_Char,
+];
+
+/// A table driven LL(1) parser building a [`Tree`] of [`SymbolTree`] nodes.
+pub struct Parser {
     parsing_table: ParsingTable,
     input: Vec<Token>,
     stack: Vec<Symbol>,
@@ -13,95 +33,503 @@ pub(crate) struct Parser {
 
 impl Parser {
     pub fn new(input: String) -> Self {
+        Self::from_tokens(Lexer::new(input, false).to_vec())
+    }
+
+    /// Parses an already lexed input, which must end with [`TokenType::End`].
+    pub fn from_tokens(input: Vec<Token>) -> Self {
         Self {
             parsing_table: super::add_rules(),
-            input: Lexer::new(input.clone(), false).to_vec(),
+            input,
             stack: vec![Symbol::NonTerminal(NonTerminal::Program)],
-            // ast_stack: vec![AST::new((NonTerminal::Program).as_ref().to_owned())],
         }
     }
 
-    pub fn parse(&mut self) -> Result<Tree<SymbolTree>, String> {
+    /// Parses the whole input, reporting syntax errors into `diagnostics`.
+    ///
+    /// Errors are recovered from in panic mode: tokens are skipped, a block
+    /// or parenthesized part at a time, up to a statement or block boundary
+    /// of the current nesting level where the parse can continue.
+    /// Errors found before the next token is matched are cascades of the
+    /// first one and are not reported. The tree keeps
+    /// [`SymbolTree::Error`] nodes where the input was skipped or missing.
+    ///
+    /// Returns `None` only when the input does not end with
+    /// [`TokenType::End`].
+    pub fn parse(&mut self, diagnostics: &mut Diagnostics) -> Option<Tree<SymbolTree>> {
+        let start = self
+            .input
+            .first()
+            .map(|token| token.span)
+            .unwrap_or_default();
         let mut tree = TreeBuilder::new()
-            .with_root(SymbolTree::NonTerminal(NonTerminal::Program))
+            .with_root(SymbolTree::NonTerminal(
+                NonTerminal::Program,
+                Span {
+                    end: start.start,
+                    ..start
+                },
+            ))
             .build();
         let mut index_stack = vec![tree.root_id().unwrap()];
+        let mut recovering = false;
 
         while let Some(symbol) = self.stack.pop() {
+            let ast_node = index_stack.pop().unwrap();
+            let Some(token) = self.input.first().cloned() else {
+                diagnostics.push(Diagnostic::error(
+                    code::UNEXPECTED_END,
+                    "unexpected end of input",
+                ));
+                return None;
+            };
+            // Nodes start out empty at the lookahead, token nodes get their
+            // token once it is matched.
+            let here = Span {
+                end: token.span.start,
+                ..token.span
+            };
+
             match symbol {
                 Symbol::NonTerminal(non_terminal) => {
-                    let token = self.input.first().ok_or("Unexpected end of input")?.clone();
-                    match self
+                    if !self
                         .parsing_table
-                        .get(&(non_terminal.clone(), token.token.clone()))
+                        .contains_key(&(non_terminal.clone(), token.token.clone()))
                     {
-                        Some(production) => {
-                            if production.is_empty() {
-                                continue;
-                            }
+                        // Illegal characters were already reported by the lexer.
+                        if !recovering && token.token != TokenType::ILLEGAL {
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    code::UNEXPECTED_TOKEN,
+                                    format!(
+                                        "expected {}, found {}",
+                                        one_of(&self.expected(&non_terminal)),
+                                        found(&token)
+                                    ),
+                                )
+                                .at(&token),
+                            );
+                        }
+                        recovering = true;
 
-                            let ast_node = index_stack.pop().unwrap();
-
-                            for symbol in production.iter().rev() {
-                                self.stack.push(symbol.clone());
-                                index_stack.push(
-                                    tree.get_mut(ast_node)
-                                        .unwrap()
-                                        .append(match symbol {
-                                            Symbol::Token(_) => SymbolTree::Token(token.clone()),
-                                            Symbol::NonTerminal(non) => {
-                                                SymbolTree::NonTerminal(non.clone())
-                                            }
-                                            Symbol::Def => todo!(),
-                                        })
-                                        .node_id(),
-                                );
+                        let skipped = self.skip_for(&non_terminal);
+                        let resume = self.input.first().is_some_and(|token| {
+                            self.parsing_table
+                                .contains_key(&(non_terminal.clone(), token.token.clone()))
+                        });
+                        if !resume {
+                            // Give up on `non_terminal`.
+                            let mut node = tree.get_mut(ast_node).unwrap();
+                            *node.data() = SymbolTree::Error(here);
+                            for token in skipped {
+                                node.append(SymbolTree::Token(token));
                             }
+                            continue;
                         }
-                        None => {
-                            println!("No rule for {:?}  with {:?}", &non_terminal, &token);
-                            self.handel_err();
+                        if !skipped.is_empty() {
+                            insert_error_before(&mut tree, ast_node, here, skipped);
                         }
                     }
+
+                    let token = self.input.first().unwrap();
+                    let here = Span {
+                        end: token.span.start,
+                        ..token.span
+                    };
+                    let production =
+                        &self.parsing_table[&(non_terminal.clone(), token.token.clone())];
+
+                    let mut node = tree.get_mut(ast_node).unwrap();
+                    *node.data() = SymbolTree::NonTerminal(non_terminal.clone(), here);
+                    let children: Vec<_> = production
+                        .iter()
+                        .map(|symbol| {
+                            node.append(match symbol {
+                                Symbol::Token(token_type) => SymbolTree::Token(Token {
+                                    token: token_type.clone(),
+                                    literal: String::new(),
+                                    span: here,
+                                }),
+                                Symbol::NonTerminal(non) => {
+                                    SymbolTree::NonTerminal(non.clone(), here)
+                                }
+                                Symbol::Def => unreachable!("productions never contain `Def`"),
+                            })
+                            .node_id()
+                        })
+                        .collect();
+
+                    for (symbol, child) in production.iter().zip(children).rev() {
+                        self.stack.push(symbol.clone());
+                        index_stack.push(child);
+                    }
                 }
                 Symbol::Token(expected_token) => {
-                    if let Some(token) = self.input.first() {
-                        if expected_token == token.token {
-                            index_stack.pop();
-                            self.input.remove(0); // Consume the token.
-                        } else {
-                            println!("Expected {:?}, found {:?}", expected_token, token);
-                            self.handel_err();
-                        }
+                    if expected_token == token.token {
+                        // Consume the token.
+                        *tree.get_mut(ast_node).unwrap().data() =
+                            SymbolTree::Token(self.input.remove(0));
+                        recovering = false;
                     } else {
-                        return Err("Unexpected end of input".to_string());
+                        // Carry on as if the token was there.
+                        if !recovering && token.token != TokenType::ILLEGAL {
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    code::EXPECTED_TOKEN,
+                                    format!(
+                                        "expected {}, found {}",
+                                        expected_token.describe(),
+                                        found(&token)
+                                    ),
+                                )
+                                .at(&token),
+                            );
+                        }
+                        recovering = true;
+                        *tree.get_mut(ast_node).unwrap().data() = SymbolTree::Error(here);
                     }
                 }
-                _ => panic!("how the fuck "),
+                Symbol::Def => unreachable!("`Def` is never pushed"),
             }
         }
 
-        if self.input.first().unwrap().token != TokenType::End {
-            return Err("Input not fully consumed".to_string());
+        match self.input.first() {
+            Some(token) if token.token == TokenType::End => {
+                let root = tree.root_id().unwrap();
+                fill_spans(&mut tree, root);
+                Some(tree)
+            }
+            Some(token) => {
+                diagnostics.push(
+                    Diagnostic::error(code::TRAILING_INPUT, "input not fully consumed").at(token),
+                );
+                None
+            }
+            None => {
+                diagnostics.push(Diagnostic::error(
+                    code::UNEXPECTED_END,
+                    "unexpected end of input",
+                ));
+                None
+            }
         }
-        Ok(tree)
-    }
-    fn is_synchronization_token(&self, token: &TokenType) -> bool {
-        [TokenType::T_Semicolon, TokenType::T_RP].contains(&token)
     }
 
-    fn handel_err(&mut self) {
-        while let Some(Symbol::Token(token)) = self.stack.pop() {
-            if self.is_synchronization_token(&token) {
+    /// Skips tokens until the parse can continue at the current nesting
+    /// level: at a `;` or `}`, a `)`, `]` or `,` outside any skipped
+    /// parentheses, or a [`SYNC_TOKENS`] token, when `non_terminal`, its
+    /// FOLLOW set or the rest of the stack can continue there. After a
+    /// skipped `;` or `}`, also at any token `non_terminal` can start with.
+    /// Tokens between a skipped `{` and its `}` are skipped as a whole.
+    /// Never skips [`TokenType::End`].
+    fn skip_for(&mut self, non_terminal: &NonTerminal) -> Vec<Token> {
+        let mut skipped: Vec<Token> = vec![];
+        // Braces and parentheses or brackets opened by skipped tokens.
+        let (mut braces, mut parens) = (0usize, 0usize);
+        while let Some(token) = self.input.first() {
+            let token = &token.token;
+            let starts = self
+                .parsing_table
+                .contains_key(&(non_terminal.clone(), token.clone()));
+            let continues =
+                || starts || follow_set(non_terminal).contains(token) || self.stack_accepts(token);
+            let after_boundary = braces == 0
+                && skipped.last().is_some_and(|last| {
+                    matches!(last.token, TokenType::T_Semicolon | TokenType::T_RC)
+                });
+            let stop = match token {
+                TokenType::End => true,
+                TokenType::T_RP | TokenType::T_RB | TokenType::T_Comma => {
+                    braces == 0 && parens == 0 && continues()
+                }
+                token if SYNC_TOKENS.contains(token) => braces == 0 && continues(),
+                _ => after_boundary && starts,
+            };
+            if stop {
                 break;
             }
+            match token {
+                TokenType::T_LC => braces += 1,
+                TokenType::T_RC => braces = braces.saturating_sub(1),
+                TokenType::T_LP | TokenType::T_LB => parens += 1,
+                TokenType::T_RP | TokenType::T_RB => parens = parens.saturating_sub(1),
+                _ => {}
+            }
+            skipped.push(self.input.remove(0));
         }
+        skipped
+    }
 
-        while let Some(next_token) = self.input.iter().peekable().peek() {
-            if self.is_synchronization_token(&next_token.token) {
-                break;
+    /// The tokens `non_terminal` can start with: its row of the parsing
+    /// table, in the order [`TokenType`] declares them.
+    fn expected(&self, non_terminal: &NonTerminal) -> Vec<TokenType> {
+        let mut expected: Vec<TokenType> = self
+            .parsing_table
+            .keys()
+            .filter(|(row, _)| row == non_terminal)
+            .map(|(_, token)| token.clone())
+            .collect();
+        expected.sort_by_key(|token| token.clone() as usize);
+        expected
+    }
+
+    /// Whether some symbol left on the stack can continue at `token`.
+    fn stack_accepts(&self, token: &TokenType) -> bool {
+        self.stack.iter().any(|symbol| match symbol {
+            Symbol::Token(expected) => expected == token,
+            Symbol::NonTerminal(non_terminal) => self
+                .parsing_table
+                .contains_key(&(non_terminal.clone(), token.clone())),
+            Symbol::Def => false,
+        })
+    }
+}
+
+/// Lists `tokens` for a diagnostic as "`a`, `b` or `c`". Token types that
+/// read the same, like the two kinds of number, are listed once.
+fn one_of(tokens: &[TokenType]) -> String {
+    let mut names: Vec<&str> = vec![];
+    for token in tokens {
+        if !names.contains(&token.describe()) {
+            names.push(token.describe());
+        }
+    }
+    match names.split_last() {
+        Some((last, [])) => last.to_string(),
+        Some((last, rest)) => format!("{} or {last}", rest.join(", ")),
+        None => "nothing".to_owned(),
+    }
+}
+
+/// How `token` reads in a diagnostic.
+fn found(token: &Token) -> String {
+    match token.token {
+        TokenType::End => token.token.describe().to_owned(),
+        _ => format!("`{}`", token.literal),
+    }
+}
+
+/// Puts an error node holding `skipped` right before `node`, or as its first
+/// child when it is the root.
+fn insert_error_before(tree: &mut Tree<SymbolTree>, node: NodeId, here: Span, skipped: Vec<Token>) {
+    let parent = tree
+        .get(node)
+        .unwrap()
+        .parent()
+        .map(|parent| parent.node_id());
+    let error = match parent {
+        Some(parent) => {
+            let mut error = tree
+                .get_mut(parent)
+                .unwrap()
+                .append(SymbolTree::Error(here))
+                .node_id();
+            while tree
+                .get(error)
+                .unwrap()
+                .next_sibling()
+                .is_none_or(|next| next.node_id() != node)
+            {
+                let mut error_node = tree.get_mut(error).unwrap();
+                error_node.swap_prev_sibling();
+                error = error_node.node_id();
+            }
+            error
+        }
+        None => tree
+            .get_mut(node)
+            .unwrap()
+            .prepend(SymbolTree::Error(here))
+            .node_id(),
+    };
+    let mut error = tree.get_mut(error).unwrap();
+    for token in skipped {
+        error.append(SymbolTree::Token(token));
+    }
+}
+
+/// Widens the span of every non-terminal and error node under `node` to
+/// cover its children.
+fn fill_spans(tree: &mut Tree<SymbolTree>, node: NodeId) {
+    // Children come after their parent in pre-order, so going backwards
+    // fills them first. Deep trees would overflow the stack with recursion.
+    let nodes: Vec<NodeId> = tree
+        .get(node)
+        .unwrap()
+        .traverse_pre_order()
+        .map(|node| node.node_id())
+        .collect();
+    for node in nodes.into_iter().rev() {
+        let span = tree
+            .get(node)
+            .unwrap()
+            .children()
+            .map(|child| child.data().span())
+            .filter(|span| !span.is_empty())
+            .reduce(Span::to);
+        if let (SymbolTree::NonTerminal(_, own) | SymbolTree::Error(own), Some(span)) =
+            (tree.get_mut(node).unwrap().data(), span)
+        {
+            *own = span;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tree_is_in_source_order_with_spans() {
+        let source = "int x = 1;";
+        let tree = Parser::new(source.to_owned())
+            .parse(&mut Diagnostics::new())
+            .unwrap();
+
+        let mut leaves = vec![];
+        let mut stack = vec![tree.root().unwrap()];
+        while let Some(node) = stack.pop() {
+            if let SymbolTree::Token(token) = node.data() {
+                leaves.push(&source[token.span.start..token.span.end]);
             }
-            self.input.remove(0);
+            let mut children: Vec<_> = node.children().collect();
+            children.reverse();
+            stack.extend(children);
         }
+        assert_eq!(leaves, ["int", "x", "=", "1", ";"]);
+
+        let root = tree.root().unwrap();
+        assert_eq!((root.data().span().start, root.data().span().end), (0, 10));
+        let declarations = root.first_child().unwrap();
+        let declaration = declarations.first_child().unwrap();
+        assert!(declaration.data().is(NonTerminal::Declaration));
+        assert_eq!(declaration.data().span().end, 10);
+        // `Declarations -> ''` sits empty at the end of the input.
+        let rest = declarations.last_child().unwrap();
+        assert_eq!((rest.data().span().start, rest.data().span().end), (10, 10));
+    }
+
+    #[test]
+    fn parses_names_in_expressions_else_if_and_hex() {
+        let source = "int f(int a) { return a; }
+int main() {
+    int x = 0x1F;
+    if (x > 1) { x = f(x) + 1; } else if (!(x < 0)) { print(\"x\", x); } else { f(x); }
+    for (int i = 0; i < 3; i = i + 1) { }
+    for (; x; ) { break; }
+    return x;
+}";
+        let mut diagnostics = Diagnostics::new();
+        let tree = Parser::new(source.to_owned()).parse(&mut diagnostics);
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        assert!(tree.is_some());
+    }
+
+    #[test]
+    fn recovers_from_each_error_and_keeps_the_tree_in_sync() {
+        let source = "int main() {
+    int x = ;
+    x = (1 + ;
+    int y = 2 3;
+}
+int g(int a) { return a; }";
+        let mut diagnostics = Diagnostics::new();
+        let tree = Parser::new(source.to_owned())
+            .parse(&mut diagnostics)
+            .unwrap();
+        let lines: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.code, diagnostic.span.unwrap().line))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                (code::UNEXPECTED_TOKEN, 2),
+                (code::UNEXPECTED_TOKEN, 3),
+                (code::UNEXPECTED_TOKEN, 4)
+            ]
+        );
+
+        let root = tree.root().unwrap();
+        let errors: Vec<_> = root
+            .traverse_pre_order()
+            .filter(|node| node.data().is_error())
+            .map(|node| &source[node.data().span().start..node.data().span().end])
+            .collect();
+        assert!(errors.contains(&"3"), "{errors:?}");
+
+        // The declaration after the errors is parsed normally.
+        let names: Vec<_> = root
+            .traverse_pre_order()
+            .filter(|node| node.data().is(NonTerminal::Identifier))
+            .filter_map(|node| {
+                node.first_child()?
+                    .data()
+                    .token()
+                    .map(|t| t.literal.clone())
+            })
+            .collect();
+        assert_eq!(names, ["main", "x", "x", "y", "g", "a", "a"]);
+    }
+
+    #[test]
+    fn skips_nested_blocks_and_parentheses_as_a_whole() {
+        // Not even the checks after parsing find more problems.
+        let codes = |source: &str| {
+            crate::check_with(source, crate::diagnostic::Lints::allow_all())
+                .diagnostics
+                .codes()
+        };
+        assert_eq!(
+            codes("int main() { else { } return 0; }"),
+            [code::UNEXPECTED_TOKEN]
+        );
+        assert_eq!(
+            codes("int main() { for (int i = 0; i < 3 i = i + 1) { } return 0; }"),
+            [code::UNEXPECTED_TOKEN]
+        );
+        assert_eq!(
+            codes("int main() { 5; int x = 1; x = 2; return x; }"),
+            [code::UNEXPECTED_TOKEN]
+        );
+    }
+
+    #[test]
+    fn fills_the_spans_of_deep_trees_without_recursion() {
+        let terms = vec!["1"; 5000].join(" + ");
+        let source = format!("int x = {terms};");
+        let tree = Parser::new(source.clone())
+            .parse(&mut Diagnostics::new())
+            .unwrap();
+        let span = tree.root().unwrap().data().span();
+        assert_eq!((span.start, span.end), (0, source.len()));
+    }
+
+    #[test]
+    fn reports_the_tokens_the_parsing_table_expects() {
+        let messages = |source: &str| {
+            let mut diagnostics = Diagnostics::new();
+            Parser::new(source.to_owned()).parse(&mut diagnostics);
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.message.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            messages("int f(int a b) { return a; }"),
+            ["expected `)` or `,`, found `b`"]
+        );
+        assert_eq!(
+            messages("int main() { int x = ; }"),
+            ["expected `false`, `true`, `!`, `(`, an identifier, a string, a number or a character, found `;`"]
+        );
+        assert_eq!(
+            messages("int f(int a, ) { return a; }"),
+            ["expected `bool`, `char` or `int`, found `)`"]
+        );
+        assert_eq!(
+            messages("int main() {"),
+            ["expected `bool`, `break`, `char`, `continue`, `for`, `if`, `int`, `print`, `return`, `}` or an identifier, found end of input"]
+        );
     }
 }
@@ -1,7 +1,7 @@
 use strum::EnumString;
 use strum_macros::AsRefStr;
 
-#[allow(non_camel_case_types)]
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, PartialEq, Eq, AsRefStr, Hash, EnumString, Default)]
 pub enum TokenType {
     #[default]
@@ -52,10 +52,94 @@ pub enum TokenType {
     End,
 }
 
+impl TokenType {
+    /// How the token type reads in a diagnostic: the token itself when it is
+    /// always spelled the same, what kind of token it is otherwise.
+    pub fn describe(&self) -> &'static str {
+        match self {
+            TokenType::T_Bool => "`bool`",
+            TokenType::T_Break => "`break`",
+            TokenType::T_Char => "`char`",
+            TokenType::T_Continue => "`continue`",
+            TokenType::T_Else => "`else`",
+            TokenType::T_False => "`false`",
+            TokenType::T_For => "`for`",
+            TokenType::T_If => "`if`",
+            TokenType::T_Int => "`int`",
+            TokenType::T_Print => "`print`",
+            TokenType::T_Return => "`return`",
+            TokenType::T_True => "`true`",
+            TokenType::T_AOp_PL => "`+`",
+            TokenType::T_AOp_MN => "`-`",
+            TokenType::T_AOp_ML => "`*`",
+            TokenType::T_AOp_DV => "`/`",
+            TokenType::T_AOp_RM => "`%`",
+            TokenType::T_ROp_L => "`<`",
+            TokenType::T_ROp_G => "`>`",
+            TokenType::T_ROp_LE => "`<=`",
+            TokenType::T_ROp_GE => "`>=`",
+            TokenType::T_ROp_NE => "`!=`",
+            TokenType::T_ROp_E => "`==`",
+            TokenType::T_LOp_AND => "`&&`",
+            TokenType::T_LOp_OR => "`||`",
+            TokenType::T_LOp_NOT => "`!`",
+            TokenType::T_Assign => "`=`",
+            TokenType::T_LP => "`(`",
+            TokenType::T_RP => "`)`",
+            TokenType::T_LC => "`{`",
+            TokenType::T_RC => "`}`",
+            TokenType::T_LB => "`[`",
+            TokenType::T_RB => "`]`",
+            TokenType::T_Semicolon => "`;`",
+            TokenType::T_Comma => "`,`",
+            TokenType::T_Id => "an identifier",
+            TokenType::T_String => "a string",
+            TokenType::T_Decimal | TokenType::T_Hexadecimal => "a number",
+            TokenType::T_Character => "a character",
+            TokenType::T_Whitespace | TokenType::T_Newline => "whitespace",
+            TokenType::T_Comment => "a comment",
+            TokenType::ILLEGAL => "an illegal character",
+            TokenType::End => "end of input",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct Token {
     pub token: TokenType,
-    pub line: usize,
     pub literal: String,
+    pub span: Span,
+}
+
+/// A range of the source: `start..end` in bytes, plus the line and column
+/// (both 1-based, the column counted in characters) of `start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
     pub column: usize,
 }
+
+impl Span {
+    /// The smallest span covering both `self` and `other`.
+    pub fn to(self, other: Span) -> Span {
+        let (first, last) = if self.start <= other.start {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        Span {
+            end: first.end.max(last.end),
+            ..first
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+}